@@ -0,0 +1,31 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fdm::base::Simluation;
+use fdm::boundary::Boundary;
+
+// the ghost cells of a 100k-cell state, as fetched by every scheme on every step
+fn ghost_cells(c: &mut Criterion) {
+    let n = 100_000;
+    let mut sim = Simluation::with_cells([-1., 1.], n, 1e-6, |x: f64| (-x * x).exp());
+    for &(name, boundary) in [
+        ("periodic", Boundary::Periodic),
+        ("dirichlet", Boundary::Dirichlet([1., 0.])),
+    ]
+    .iter()
+    {
+        sim.set_boundary(boundary);
+        c.bench_function(&format!("get_u 100k {}", name), |b| {
+            b.iter(|| black_box(sim.get_u(black_box(3))))
+        });
+
+        let mut scratch = vec![];
+        c.bench_function(&format!("get_u_into 100k {}", name), |b| {
+            b.iter(|| {
+                sim.get_u_into(black_box(3), &mut scratch);
+                black_box(&scratch);
+            })
+        });
+    }
+}
+
+criterion_group!(benches, ghost_cells);
+criterion_main!(benches);
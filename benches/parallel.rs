@@ -0,0 +1,37 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fdm::base::Simluation;
+use fdm::equations::BuckleyLeverett;
+use fdm::schemes::{LaxWendroff, Scheme, Upwind};
+use rayon::ThreadPoolBuilder;
+use std::f64::consts::PI;
+
+// a step of a 1M-cell grid on a single thread against the whole thread pool,
+// the speedup being the ratio of the two
+fn parallel_flux(c: &mut Criterion) {
+    let n = 1_000_000;
+    let sim = Simluation::with_cells([-1., 1.], n, 1e-7, |x: f64| 0.5 + 0.4 * (PI * x).sin());
+    let eq = BuckleyLeverett {
+        mobility_ratio: 0.5,
+    };
+    let serial = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let pool = ThreadPoolBuilder::new().build().unwrap();
+
+    let schemes: [(&str, &(dyn Scheme<f64> + Sync)); 2] =
+        [("Upwind", &Upwind), ("LaxWendroff", &LaxWendroff)];
+    for &(name, scheme) in schemes.iter() {
+        c.bench_function(&format!("{} 1M serial", name), |b| {
+            b.iter(|| serial.install(|| black_box(scheme.run(&sim, &eq).unwrap())))
+        });
+        c.bench_function(
+            &format!("{} 1M on {} threads", name, pool.current_num_threads()),
+            |b| b.iter(|| pool.install(|| black_box(scheme.run(&sim, &eq).unwrap()))),
+        );
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = parallel_flux
+}
+criterion_main!(benches);
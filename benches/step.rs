@@ -0,0 +1,26 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fdm::base::Simluation;
+use fdm::equations::InviscidBurger;
+use fdm::schemes::{LaxWendroff, Scheme};
+use std::f64::consts::PI;
+
+// a fresh state from `run` on every step against `step` reusing the previous one
+fn step(c: &mut Criterion) {
+    let n = 2000;
+    let init = |x: f64| 0.5 + 0.4 * (PI * x).sin();
+    let mut replaced = Simluation::with_cells([-1., 1.], n, 1e-4, init);
+    let mut stepped = replaced.clone();
+
+    c.bench_function("run and set_state 2k", |b| {
+        b.iter(|| {
+            let state = LaxWendroff.run(&replaced, &InviscidBurger).unwrap();
+            replaced.set_state(black_box(state));
+        })
+    });
+    c.bench_function("step 2k", |b| {
+        b.iter(|| black_box(stepped.step(&LaxWendroff, &InviscidBurger).unwrap()))
+    });
+}
+
+criterion_group!(benches, step);
+criterion_main!(benches);
@@ -1,7 +1,8 @@
 use clap::Clap;
 use fdm::base::Simluation;
-use fdm::equations::{Advection, InviscidBurger};
-use fdm::schemes::{BeamWarming, LaxFriedrichs, LaxWendroff, Upwind};
+use fdm::equations::{Advection, BuckleyLeverett, InviscidBurger};
+use fdm::plot::Animation;
+use fdm::schemes::{BeamWarming, LaxFriedrichs, LaxWendroff, MacCormack, Upwind};
 use fdm::{BoxedEquation, BoxedFunction, BoxedScheme};
 use gnuplot::{AxesCommon, Figure, Fix, Font};
 use itertools::iproduct;
@@ -13,6 +14,27 @@ use std::fs;
 struct Args {
     #[clap(short, long, default_value = "outputs")]
     output_dir: String,
+    /// Delay between animation frames in 1/100 s
+    #[clap(long, default_value = "2")]
+    delay: u32,
+    #[clap(long, default_value = "480")]
+    width: u32,
+    #[clap(long, default_value = "360")]
+    height: u32,
+    /// Skip the gif optimization
+    #[clap(long)]
+    no_optimize: bool,
+}
+
+impl Args {
+    fn animation(&self) -> Animation {
+        Animation {
+            delay: self.delay,
+            width: self.width,
+            height: self.height,
+            optimize: !self.no_optimize,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -37,14 +59,12 @@ struct Experiment<'a> {
 }
 
 impl Experiment<'_> {
-    fn run(&self, output_dir: &str, domain: &Domain) {
+    fn run(&self, output_dir: &str, domain: &Domain, animation: &Animation) {
         let mut fig = Figure::new();
         let name = format!("{}-{}-{}", self.name.equ, self.name.ini, self.name.sch);
         println!("Processing {}", name);
-        fig.set_title(&name).set_terminal(
-            "gif animate optimize delay 2 size 480,360",
-            &format!("{}/{}.gif", output_dir, name),
-        );
+        fig.set_title(&name);
+        animation.apply(&mut fig, &format!("{}/{}.gif", output_dir, name));
 
         let mut sim = Simluation::<f64>::new(domain.dx, domain.dt, domain.space, self.ini);
 
@@ -60,7 +80,11 @@ impl Experiment<'_> {
                 .set_y_range(Fix(-1.5), Fix(1.5))
                 .set_x_range(Fix(domain.space[0]), Fix(domain.space[1]));
 
-            sim.set_state(self.sch.run(&sim, &**self.equ));
+            // skip a failing experiment without stopping the others
+            if let Err(e) = sim.step(&**self.sch, &**self.equ) {
+                println!("Skipping {}: {}", name, e);
+                return;
+            }
             ax.lines(&sim.grid, &sim.state, &[]);
         }
 
@@ -88,6 +112,12 @@ fn main() {
     let equations: Vec<(&str, BoxedEquation)> = vec![
         ("Advection", Box::new(Advection { a: 1.0 })),
         ("InviscidBurger", Box::new(InviscidBurger)),
+        (
+            "BuckleyLeverett",
+            Box::new(BuckleyLeverett {
+                mobility_ratio: 0.5,
+            }),
+        ),
     ];
 
     // initial waves
@@ -95,7 +125,7 @@ fn main() {
         ("Sine", Box::new(|x: f64| (PI * x).sin())),
         (
             "Square",
-            Box::new(|x: f64| if x >= 0. && x <= 1. { 1.0 } else { 0. }),
+            Box::new(|x: f64| if (0. ..=1.).contains(&x) { 1.0 } else { 0. }),
         ),
     ];
 
@@ -105,6 +135,7 @@ fn main() {
         ("BeamWarming", Box::new(BeamWarming)),
         ("LaxWendroff", Box::new(LaxWendroff)),
         ("LaxFriedrichs", Box::new(LaxFriedrichs)),
+        ("MacCormack", Box::new(MacCormack)),
     ];
 
     let exps: Vec<Experiment> = iproduct!(equations.iter(), inits.iter(), schemes.iter())
@@ -120,6 +151,7 @@ fn main() {
         })
         .collect();
 
+    let animation = args.animation();
     exps.into_par_iter()
-        .for_each(|exp| exp.run(&args.output_dir, &domain));
+        .for_each(|exp| exp.run(&args.output_dir, &domain, &animation));
 }
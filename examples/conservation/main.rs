@@ -1,47 +1,101 @@
 use clap::Clap;
 use fdm::base::{Equation, Simluation};
-use fdm::equations::{Advection, InviscidBurger};
-use fdm::schemes::{BeamWarming, LaxFriedrichs, LaxWendroff, Scheme, Upwind};
+use fdm::equations::{Advection, BuckleyLeverett, InviscidBurger};
+use fdm::numeric::AsF64;
+use fdm::plot::Animation;
+use fdm::schemes::{BeamWarming, LaxFriedrichs, LaxWendroff, MacCormack, Scheme, Upwind};
 use gnuplot::{AxesCommon, Figure, Fix, Font};
 use itertools::iproduct;
+use num_traits::Float;
 use std::collections::HashMap;
 use std::f64::consts::PI;
+use std::fmt::Debug;
 use std::fs;
 
 #[derive(Clap)]
 struct Args {
     #[clap(short, long, default_value = "outputs")]
     output_dir: String,
+    /// Delay between animation frames in 1/100 s
+    #[clap(long, default_value = "2")]
+    delay: u32,
+    #[clap(long, default_value = "480")]
+    width: u32,
+    #[clap(long, default_value = "360")]
+    height: u32,
+    /// Skip the gif optimization
+    #[clap(long)]
+    no_optimize: bool,
+    /// Run in f32 instead of f64
+    #[clap(long)]
+    single: bool,
+}
+
+impl Args {
+    fn animation(&self) -> Animation {
+        Animation {
+            delay: self.delay,
+            width: self.width,
+            height: self.height,
+            optimize: !self.no_optimize,
+        }
+    }
 }
 
 fn main() {
     let args = Args::parse();
     fs::create_dir_all(&args.output_dir).unwrap();
+    if args.single {
+        run::<f32>(&args);
+    } else {
+        run::<f64>(&args);
+    }
+}
+
+fn run<T>(args: &Args)
+where
+    T: Float + Debug + Send + Sync + AsF64 + 'static,
+{
+    let animation = args.animation();
+    let c = |x: f64| T::from(x).unwrap();
 
     // equations
-    let mut eqs: HashMap<String, Box<dyn Equation<f64>>> = HashMap::new();
-    eqs.insert("Advection".into(), Box::new(Advection { a: 1.0 }));
+    let mut eqs: HashMap<String, Box<dyn Equation<T>>> = HashMap::new();
+    eqs.insert("Advection".into(), Box::new(Advection { a: c(1.0) }));
     eqs.insert("InviscidBurger".into(), Box::new(InviscidBurger));
+    eqs.insert(
+        "BuckleyLeverett".into(),
+        Box::new(BuckleyLeverett {
+            mobility_ratio: c(0.5),
+        }),
+    );
 
     // conditions
-    let dx = 1e-2;
-    let cfl = 0.6;
+    let dx = c(1e-2);
+    let cfl = c(0.6);
     let dt = cfl * dx;
-    let boundary = [-3., 3.];
+    let boundary = [c(-3.), c(3.)];
 
     // schemes
-    let mut schemes: HashMap<String, Box<dyn Scheme<f64>>> = HashMap::new();
+    let mut schemes: HashMap<String, Box<dyn Scheme<T>>> = HashMap::new();
     schemes.insert("Upwind".into(), Box::new(Upwind));
     schemes.insert("BeamWarming".into(), Box::new(BeamWarming));
     schemes.insert("LaxWendroff".into(), Box::new(LaxWendroff));
     schemes.insert("LaxFriedrichs".into(), Box::new(LaxFriedrichs));
+    schemes.insert("MacCormack".into(), Box::new(MacCormack));
 
     // initial waves
-    let mut inits: HashMap<String, Box<dyn Fn(f64) -> f64>> = HashMap::new();
-    inits.insert("Sine".into(), Box::new(|x: f64| (PI * x).sin()));
+    let mut inits: HashMap<String, Box<dyn Fn(T) -> T>> = HashMap::new();
+    inits.insert("Sine".into(), Box::new(move |x: T| (c(PI) * x).sin()));
     inits.insert(
         "Square".into(),
-        Box::new(|x: f64| if x >= 0. && x <= 1. { 1.0 } else { 0. }),
+        Box::new(move |x: T| {
+            if (c(0.)..=c(1.)).contains(&x) {
+                c(1.)
+            } else {
+                c(0.)
+            }
+        }),
     );
 
     for ((eq_name, eq), (init_name, init), (scheme_name, scheme)) in
@@ -50,14 +104,12 @@ fn main() {
         let mut fig = Figure::new();
         let name = format!("{}-{}-{}", eq_name, init_name, scheme_name);
         println!("Processing {}", name);
-        fig.set_title(&name).set_terminal(
-            "gif animate optimize delay 2 size 480,360",
-            &format!("{}/{}.gif", args.output_dir, name),
-        );
+        fig.set_title(&name);
+        animation.apply(&mut fig, &format!("{}/{}.gif", args.output_dir, name));
 
-        let mut sim = Simluation::<f64>::new(dx, dt, boundary, init);
+        let mut sim = Simluation::<T>::new(dx, dt, boundary, init);
 
-        for i in 0..(3. / dt) as i32 {
+        for i in 0..(3. / dt.to_f64().unwrap()) as i32 {
             if i > 0 {
                 fig.new_page();
             }
@@ -67,10 +119,14 @@ fn main() {
                 .set_x_grid(true)
                 .set_y_grid(true)
                 .set_y_range(Fix(-1.5), Fix(1.5))
-                .set_x_range(Fix(boundary[0]), Fix(boundary[1]));
+                .set_x_range(Fix(-3.), Fix(3.));
 
-            sim.set_state(scheme.run(&sim, &**eq));
-            ax.lines(&sim.grid, &sim.state, &[]);
+            // the fastest Buckley-Leverett waves exceed the CFL limit
+            if let Err(e) = sim.step(&**scheme, &**eq) {
+                println!("Stopping {}: {}", name, e);
+                break;
+            }
+            ax.lines(&sim.grid_view_f64(), &sim.state_view_f64(), &[]);
         }
 
         fig.show().unwrap();
@@ -0,0 +1,83 @@
+use clap::Clap;
+use fdm::boundary::Boundary;
+use fdm::plot::Animation;
+use fdm::systems::{ShallowWater, SystemSimulation};
+use gnuplot::{AxesCommon, Caption, Figure, Fix, Font};
+use std::fs;
+
+#[derive(Clap)]
+struct Args {
+    #[clap(short, long, default_value = "outputs")]
+    output_dir: String,
+    /// Delay between animation frames in 1/100 s
+    #[clap(long, default_value = "2")]
+    delay: u32,
+    #[clap(long, default_value = "480")]
+    width: u32,
+    #[clap(long, default_value = "360")]
+    height: u32,
+    /// Skip the gif optimization
+    #[clap(long)]
+    no_optimize: bool,
+}
+
+impl Args {
+    fn animation(&self) -> Animation {
+        Animation {
+            delay: self.delay,
+            width: self.width,
+            height: self.height,
+            optimize: !self.no_optimize,
+        }
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    fs::create_dir_all(&args.output_dir).unwrap();
+
+    // conditions
+    let dx = 1e-2;
+    let dt = 0.25 * dx;
+    let range = [-3., 3.];
+    let eq = ShallowWater { g: 1.0 };
+
+    // still water, twice as deep on the left of the dam
+    let mut sim =
+        SystemSimulation::new(dx, dt, range, |x: f64| [if x < 0. { 2. } else { 1. }, 0.]).unwrap();
+    sim.set_boundary(Boundary::Neumann);
+
+    let name = "ShallowWater-DamBreak-Rusanov";
+    println!("Processing {}", name);
+    let mut fig = Figure::new();
+    fig.set_title(name);
+    args.animation()
+        .apply(&mut fig, &format!("{}/{}.gif", args.output_dir, name));
+
+    for i in 0..(1.5 / dt) as i32 {
+        if let Err(e) = sim.step(&eq) {
+            println!("Stopping {}: {}", name, e);
+            break;
+        }
+        // a frame every 10 steps
+        if i % 10 != 0 {
+            continue;
+        }
+        if i > 0 {
+            fig.new_page();
+        }
+        // the rarefaction runs left, the shock right
+        let h = sim.state.column(0).to_vec();
+        let u: Vec<f64> = sim.state.outer_iter().map(|c| c[1] / c[0]).collect();
+        fig.axes2d()
+            .set_title(name, &[Font("Times", 20.0)])
+            .set_x_grid(true)
+            .set_y_grid(true)
+            .set_y_range(Fix(-0.5), Fix(2.5))
+            .set_x_range(Fix(range[0]), Fix(range[1]))
+            .lines(&sim.grid, &h, &[Caption("h")])
+            .lines(&sim.grid, &u, &[Caption("u")]);
+    }
+
+    fig.show().unwrap();
+}
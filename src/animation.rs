@@ -0,0 +1,115 @@
+//! # Animation
+//!
+//! Streaming GIF output, available with the `gif` feature.
+//!
+//! Instead of collecting every frame in a gnuplot `Figure` until `show()` is
+//! called, [`GifWriter`] rasterizes each frame and encodes it to the
+//! underlying writer right away, so memory stays bounded however long the
+//! animation runs.
+
+use crate::base::Simluation;
+use gif::{Encoder, EncodingError, Frame, Repeat};
+use num_traits::Float;
+use std::io::{self, Write};
+
+// white background, black line
+const PALETTE: [u8; 6] = [0xff, 0xff, 0xff, 0x00, 0x00, 0x00];
+const BACKGROUND: u8 = 0;
+const LINE: u8 = 1;
+
+pub struct GifWriter<W: Write> {
+    encoder: Encoder<W>,
+    width: u16,
+    height: u16,
+    y_range: [f64; 2],
+    delay: u16,
+    frames: usize,
+}
+
+impl<W: Write> GifWriter<W> {
+    /// Start an endlessly looping animation of `width` x `height` pixels,
+    /// showing the state within `y_range`.
+    pub fn new(
+        writer: W,
+        width: u16,
+        height: u16,
+        y_range: [f64; 2],
+    ) -> Result<Self, EncodingError> {
+        let mut encoder = Encoder::new(writer, width, height, &PALETTE)?;
+        encoder.set_repeat(Repeat::Infinite)?;
+        Ok(Self {
+            encoder,
+            width,
+            height,
+            y_range,
+            delay: 2,
+            frames: 0,
+        })
+    }
+
+    /// Set the delay between frames in hundredths of a second
+    pub fn set_delay(&mut self, delay: u16) {
+        self.delay = delay;
+    }
+
+    /// Number of frames written so far
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Rasterize the current state and write it out as the next frame
+    pub fn write_frame<T: Float>(&mut self, sim: &Simluation<T>) -> Result<(), EncodingError> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut pixels = vec![BACKGROUND; width * height];
+
+        if !sim.is_empty() && width > 0 && height > 0 {
+            let n = sim.len();
+            let [y_min, y_max] = self.y_range;
+            let row = |u: T| {
+                let y = (y_max - u.to_f64().unwrap()) / (y_max - y_min);
+                (y * (height - 1) as f64).max(0.).min((height - 1) as f64) as usize
+            };
+
+            // connect the samples of neighbouring columns with vertical runs
+            let mut prev: Option<usize> = None;
+            for col in 0..width {
+                let j = if width > 1 {
+                    col * (n - 1) / (width - 1)
+                } else {
+                    0
+                };
+                let r = row(sim.state[j]);
+                let (lo, hi) = match prev {
+                    Some(p) if p < r => (p, r),
+                    Some(p) => (r, p),
+                    None => (r, r),
+                };
+                for r in lo..=hi {
+                    pixels[r * width + col] = LINE;
+                }
+                prev = Some(r);
+            }
+        }
+
+        let mut frame = Frame::from_indexed_pixels(self.width, self.height, &pixels, None);
+        frame.delay = self.delay;
+        self.encoder.write_frame(&frame)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Write the GIF trailer and hand back the underlying writer
+    pub fn finish(self) -> io::Result<W> {
+        self.encoder.into_inner()
+    }
+}
+
+impl<T> Simluation<T>
+where
+    T: Float,
+{
+    /// Append the current state as a frame of a streaming GIF
+    pub fn frame_to_gif<W: Write>(&self, gif: &mut GifWriter<W>) -> Result<(), EncodingError> {
+        gif.write_frame(self)
+    }
+}
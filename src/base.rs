@@ -1,19 +1,193 @@
+use crate::boundary::{Boundary, BoundaryKind, GhostFill};
+use crate::check::{debug_sanity_check_eq, sanity_check_eq};
+use crate::error::{FdError, SchemeError};
+use crate::numeric::{AsF64, Numeric};
+use crate::schemes::Scheme;
 use gnuplot::{AxesCommon, Figure};
-use ndarray::{prelude::*, Array1};
-use num_traits::Float;
+use ndarray::{prelude::*, Array1, CowArray};
+use num_traits::{Float, NumCast};
 use std::fmt::Debug;
+use std::ops::Range;
+use std::sync::Arc;
 
+/// How `Simluation::run_until` picks the time step
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StepMode<T> {
+    /// The configured $\Delta t$
+    Fixed,
+    /// `stable_dt` of the state before every step, following the fastest
+    /// wave as it speeds up or slows down
+    Adaptive { cfl: T },
+}
+
+#[derive(Debug, Clone)]
 pub struct Simluation<T> {
     pub state: Array1<T>,
-    dt: T,
-    dx: T,
+    pub(crate) dt: T,
+    pub(crate) dx: T,
+    /// Time reached by `step`
+    pub(crate) t: T,
     pub grid: Array1<T>,
-    boundary: Option<[T; 2]>,
+    /// Left and right boundary conditions
+    pub(crate) boundary: [BoundaryKind<T>; 2],
+    ghost_fill: [Option<GhostFill>; 2],
+    obstacle: Vec<(usize, T)>,
+    initial_mass: T,
+    /// Time step of `stable_dt` for a state without any wave speed
+    fallback_dt: Option<T>,
+    equation: Option<Arc<dyn Equation<T> + Send + Sync>>,
+    /// The state before the last `step`, reused as the buffer of the next one
+    spare_state: Array1<T>,
+    /// States after each `step`, once `enable_history` was called
+    pub(crate) history: Option<Vec<Array1<T>>>,
+}
+
+/// Cloning of boxed equations, e.g. to reuse one configuration across
+/// several experiments
+///
+/// Every `Equation` gets it from the blanket impl, which is why an equation
+/// must be `Clone`. The lifetime lets equations borrowing another one, like
+/// `WarmCache`, be cloned as well.
+pub trait CloneEquation<T> {
+    fn clone_equation<'a>(&self) -> Box<dyn Equation<T> + 'a>
+    where
+        Self: 'a;
+
+    /// Like `clone_equation`, but keeping the box shareable between threads
+    fn clone_equation_sync<'a>(&self) -> Box<dyn Equation<T> + Send + Sync + 'a>
+    where
+        Self: Send + Sync + 'a;
+}
+
+impl<E, T> CloneEquation<T> for E
+where
+    E: Equation<T> + Clone,
+{
+    fn clone_equation<'a>(&self) -> Box<dyn Equation<T> + 'a>
+    where
+        Self: 'a,
+    {
+        Box::new(self.clone())
+    }
+
+    fn clone_equation_sync<'a>(&self) -> Box<dyn Equation<T> + Send + Sync + 'a>
+    where
+        Self: Send + Sync + 'a,
+    {
+        Box::new(self.clone())
+    }
 }
 
-pub trait Equation<T>: Debug {
+impl<'a, T: 'a> Clone for Box<dyn Equation<T> + 'a> {
+    fn clone(&self) -> Self {
+        self.clone_equation()
+    }
+}
+
+impl<'a, T: 'a> Clone for Box<dyn Equation<T> + Send + Sync + 'a> {
+    fn clone(&self) -> Self {
+        self.clone_equation_sync()
+    }
+}
+
+/// The flux of a scalar conservation law
+///
+/// An equation must be `Clone`, see `CloneEquation`.
+pub trait Equation<T>: Debug + CloneEquation<T> {
     fn f(&self, u: T) -> T;
     fn df(&self, u: T) -> T;
+
+    /// $f$ over a whole array, as used by `get_f`
+    fn f_array(&self, u: &Array1<T>) -> Array1<T>
+    where
+        T: Copy,
+    {
+        u.mapv(|u| self.f(u))
+    }
+
+    /// $f^{'}$ over a whole array
+    fn df_array(&self, u: &Array1<T>) -> Array1<T>
+    where
+        T: Copy,
+    {
+        u.mapv(|u| self.df(u))
+    }
+
+    /// Coefficient $\mu$ of a dispersive term, turning the equation into
+    ///
+    /// $$
+    /// u_t + f(u)_x + \mu u_{xxx} = 0
+    /// $$
+    ///
+    /// which `Scheme::run` adds by a central third difference.
+    fn dispersion(&self) -> Option<T> {
+        None
+    }
+
+    /// Viscosity $\nu$ of a diffusive term, turning the equation into
+    ///
+    /// $$
+    /// u_t + f(u)_x = \nu u_{xx}
+    /// $$
+    ///
+    /// which `Scheme::run` adds by a central second difference. Explicit
+    /// diffusion is only stable for $\nu \Delta t / \Delta x^2 \le \frac{1}{2}$,
+    /// on top of the CFL condition of the flux.
+    fn viscosity(&self) -> Option<T> {
+        None
+    }
+
+    /// Source $s(u, x, t)$ on the right-hand side of
+    ///
+    /// $$
+    /// u_t + f(u)_x = s(u, x, t)
+    /// $$
+    ///
+    /// which `Scheme::run` adds by a forward-Euler step after the flux
+    /// update. Stiff sources are better integrated exactly by
+    /// `Simluation::coupled_step`.
+    fn source(&self, _u: T, _x: T, _t: T) -> T
+    where
+        T: NumCast,
+    {
+        T::from(0).unwrap()
+    }
+}
+
+/// Shape of the flux $f$, which decides the wave structure of a Riemann problem
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Convexity {
+    /// $f^{''} = 0$, every wave is a contact discontinuity
+    Linear,
+    /// $f^{''} > 0$
+    Convex,
+    /// $f^{''} < 0$
+    Concave,
+    /// $f^{''}$ changes sign, e.g. Buckley-Leverett
+    NonConvex,
+}
+
+/// # Conservation Law
+///
+/// A fully specified equation, for schemes that need more than $f$ and
+/// $f^{'}$, e.g. a Godunov solver relying on convexity.
+pub trait ConservationLaw<T>: Equation<T> {
+    fn convexity(&self) -> Convexity;
+
+    /// Characteristic speeds at `u`, which for a scalar law is only $f^{'}(u)$
+    fn eigenvalues(&self, u: T) -> Vec<T> {
+        vec![self.df(u)]
+    }
+
+    /// Entropy $\eta(u)$, if one is known
+    fn entropy(&self, _u: T) -> Option<T> {
+        None
+    }
+
+    /// Entropy flux $q(u)$ with $q^{'} = \eta^{'} f^{'}$, if one is known
+    fn entropy_flux(&self, _u: T) -> Option<T> {
+        None
+    }
 }
 
 impl<T> Default for Simluation<T>
@@ -27,86 +201,628 @@ where
         let n = space.len();
         Self {
             dt: cfl * dx,
-            dx: dx,
+            dx,
+            t: T::from(0).unwrap(),
             state: Array1::<T>::zeros(n),
             grid: space,
-            boundary: None,
+            boundary: [BoundaryKind::Periodic; 2],
+            ghost_fill: [None, None],
+            obstacle: vec![],
+            initial_mass: T::from(0).unwrap(),
+            fallback_dt: None,
+            equation: None,
+            spare_state: Array1::from(vec![]),
+            history: None,
         }
     }
 }
 
 impl<T> Simluation<T>
 where
-    T: Float,
+    T: Numeric,
 {
     pub fn len(&self) -> usize {
         self.state.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.state.is_empty()
+    }
+
+    /// Replace the state, which must be as long as the grid
+    ///
+    /// With `CheckMode::Warn` a state of the wrong length is rejected, keeping
+    /// the current one.
     pub fn set_state(&mut self, new_state: Array1<T>) {
-        assert_eq!(self.len(), new_state.len());
+        if !sanity_check_eq!(self.len(), new_state.len()) {
+            return;
+        }
         self.state = new_state;
+        self.apply_immersed_boundary();
     }
 
+    /// Reinitialize the state from `init` over the existing grid, resetting
+    /// the time to zero
+    ///
+    /// Reuses the grid for another initial profile, e.g. in a sweep, instead
+    /// of constructing a new `Simluation`.
+    pub fn set_state_from_fn<F>(&mut self, init: F)
+    where
+        F: Fn(T) -> T,
+    {
+        let state = self.grid.mapv(init);
+        self.set_state(state);
+        self.initial_mass = self.mass();
+        self.t = T::from(0).unwrap();
+    }
+
+    /// Advance one time step of `scheme` in place, returning the time reached
+    ///
+    /// On an error, e.g. a CFL violation or a new state of the wrong length,
+    /// neither the state nor the time change.
+    ///
+    /// The new state is written by `Scheme::run_into` into the buffer of the
+    /// state before the previous step, so a long run does not allocate a
+    /// fresh state on every step.
+    pub fn step(&mut self, scheme: &dyn Scheme<T>, eq: &dyn Equation<T>) -> Result<T, SchemeError> {
+        let mut next = std::mem::replace(&mut self.spare_state, Array1::from(vec![]));
+        if let Err(e) = scheme.run_into(self, eq, &mut next) {
+            self.spare_state = next;
+            return Err(e);
+        }
+        // as in `set_state`, a state of the wrong length is rejected
+        if !sanity_check_eq!(self.len(), next.len()) {
+            let state = next.len();
+            self.spare_state = next;
+            return Err(SchemeError::LengthMismatch {
+                grid: self.len(),
+                state,
+            });
+        }
+        self.spare_state = std::mem::replace(&mut self.state, next);
+        self.apply_immersed_boundary();
+        self.t = self.t + self.dt;
+        if let Some(history) = self.history.as_mut() {
+            history.push(self.state.clone());
+        }
+        Ok(self.t)
+    }
+
+    /// Keep a copy of the state after every `step`
+    ///
+    /// Nothing is recorded, nor allocated, until this is called.
+    pub fn enable_history(&mut self) {
+        self.history.get_or_insert_with(Vec::new);
+    }
+
+    /// States recorded by `step` since `enable_history`, oldest first
+    pub fn history(&self) -> &[Array1<T>] {
+        self.history.as_deref().unwrap_or(&[])
+    }
+
+    /// Drop the recorded states, while recording continues
+    pub fn clear_history(&mut self) {
+        if let Some(history) = self.history.as_mut() {
+            history.clear();
+        }
+    }
+
+    /// Time reached by `step`, starting from zero
+    pub fn time(&self) -> T {
+        self.t
+    }
+
+    /// Step until the time reaches `t_end`, returning the time step taken
+    /// by each step
+    ///
+    /// The last step is shortened to land exactly on `t_end`, with its CFL
+    /// check taken at the reduced $\Delta t$. A remainder below
+    /// $\sqrt{\epsilon} \Delta t$ counts as arrived, so that rounding of the
+    /// accumulated time never adds a spurious tiny step. The configured
+    /// $\Delta t$ is restored afterwards in either `mode`.
+    ///
+    /// Fails with `SchemeError::NonPositiveCfl` for an adaptive `cfl` that is
+    /// not positive, and with `SchemeError::StalledTime` once a time step
+    /// would not move the time forward, instead of looping forever.
+    pub fn run_until(
+        &mut self,
+        t_end: T,
+        scheme: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+        mode: StepMode<T>,
+    ) -> Result<Vec<T>, SchemeError>
+    where
+        T: Float,
+    {
+        let zero = T::from(0).unwrap();
+        if let StepMode::Adaptive { cfl } = mode {
+            if cfl.is_nan() || cfl <= zero {
+                return Err(SchemeError::NonPositiveCfl(cfl.to_f64().unwrap()));
+            }
+        }
+
+        let dt = self.dt;
+        let mut dts = vec![];
+        loop {
+            let full = match mode {
+                StepMode::Fixed => dt,
+                StepMode::Adaptive { cfl } => self.stable_dt(eq, cfl),
+            };
+            let remaining = t_end - self.t;
+            let tolerance = T::epsilon().sqrt() * full;
+            if remaining <= tolerance {
+                if -remaining <= tolerance {
+                    self.t = t_end;
+                }
+                return Ok(dts);
+            }
+            let next = full.min(remaining);
+            if full.is_nan() || full <= zero || self.t + next <= self.t {
+                return Err(SchemeError::StalledTime {
+                    t: self.t.to_f64().unwrap(),
+                    dt: next.to_f64().unwrap(),
+                });
+            }
+            self.dt = next;
+            let stepped = self.step(scheme, eq);
+            dts.push(self.dt);
+            self.dt = dt;
+            stepped?;
+        }
+    }
+
+    /// Mark interior cells as a solid obstacle, holding their current values
+    ///
+    /// The held values are restored on every `set_state`, so an obstacle at
+    /// rest (e.g. `u = 0` for a closed gate) blocks anything advecting into it.
+    pub fn set_obstacle(&mut self, cells: &[usize]) {
+        self.obstacle = cells.iter().map(|&j| (j, self.state[j])).collect();
+    }
+
+    /// Restore the held values of the obstacle cells
+    pub fn apply_immersed_boundary(&mut self) {
+        for &(j, u) in self.obstacle.iter() {
+            self.state[j] = u;
+        }
+    }
+
+    /// Grid of the half-open interval $[a, b)$ in steps of `dx`
+    ///
+    /// The nodes are $x_j = a + j \Delta x$ for every $x_j < b$, i.e. the
+    /// left edges of the cells, and the right end $b$ is not a node. As the
+    /// node count is $\lceil (b - a) / \Delta x \rceil$, rounding in
+    /// $(b - a) / \Delta x$ may add a node just short of $b$; `with_cells`
+    /// fixes the count instead.
     pub fn new<F>(dx: T, dt: T, range: [T; 2], init: F) -> Self
     where
         F: Fn(T) -> T,
     {
-        let grid = Array::range(range[0], range[1], dx);
+        Self::on_grid(dx, dt, half_open_grid(dx, range), init)
+    }
+
+    /// Grid of exactly `n_cells` cells of width $\Delta x = (b - a) / n$
+    /// covering $[a, b]$
+    ///
+    /// The nodes are the cell centers $x_j = a + (j + \frac{1}{2}) \Delta x$,
+    /// symmetric about the middle of the interval.
+    pub fn with_cells<F>(range: [T; 2], n_cells: usize, dt: T, init: F) -> Self
+    where
+        F: Fn(T) -> T,
+    {
+        let dx = (range[1] - range[0]) / T::from(n_cells).unwrap();
+        let half = T::from(0.5).unwrap();
+        let grid = Array1::from_shape_fn(n_cells, |i| range[0] + dx * (T::from(i).unwrap() + half));
+        Self::on_grid(dx, dt, grid, init)
+    }
+
+    fn on_grid<F>(dx: T, dt: T, grid: Array1<T>, init: F) -> Self
+    where
+        F: Fn(T) -> T,
+    {
         let state = grid.mapv(init);
-        Self {
-            dx: dx,
-            dt: dt,
-            // boundary: Some([T::from(1.).unwrap(), T::from(1.).unwrap()]),
-            boundary: None,
-            grid: grid,
-            state: state,
+        let mut sim = Self {
+            dx,
+            dt,
+            t: T::from(0).unwrap(),
+            boundary: [BoundaryKind::Periodic; 2],
+            ghost_fill: [None, None],
+            obstacle: vec![],
+            initial_mass: T::from(0).unwrap(),
+            fallback_dt: None,
+            equation: None,
+            spare_state: Array1::from(vec![]),
+            history: None,
+            grid,
+            state,
+        };
+        sim.initial_mass = sim.mass();
+        sim
+    }
+
+    /// Keep `eq` for `advance`, so that it need not be passed on every step
+    ///
+    /// The methods taking an explicit equation keep working and are not
+    /// affected by the bound one.
+    pub fn bind_equation(&mut self, eq: Box<dyn Equation<T> + Send + Sync>) {
+        self.equation = Some(eq.into());
+    }
+
+    /// The equation bound by `bind_equation`, if any
+    pub fn equation(&self) -> Option<&(dyn Equation<T> + Send + Sync)> {
+        self.equation.as_deref()
+    }
+
+    /// Like `step`, on the bound equation
+    pub fn advance(&mut self, scheme: &dyn Scheme<T>) -> Result<T, SchemeError> {
+        let eq = self
+            .equation
+            .clone()
+            .expect("No equation bound, call bind_equation first");
+        self.step(scheme, &*eq)
+    }
+
+    /// View of the state over the indices in `range`, clamped to the grid
+    pub fn state_slice(&self, range: Range<usize>) -> ArrayView1<'_, T> {
+        let range = self.clamp(range);
+        self.state.slice(s![range])
+    }
+
+    /// View of the grid over the indices in `range`, clamped to the grid
+    pub fn grid_slice(&self, range: Range<usize>) -> ArrayView1<'_, T> {
+        let range = self.clamp(range);
+        self.grid.slice(s![range])
+    }
+
+    fn clamp(&self, range: Range<usize>) -> Range<usize> {
+        let end = range.end.min(self.len());
+        range.start.min(end)..end
+    }
+
+    /// Replace the boundary condition, taking effect on the next call of `get_u`.
+    ///
+    /// This can be called between steps, e.g. to switch from a fixed inflow
+    /// to a periodic domain mid-run.
+    pub fn set_boundary(&mut self, boundary: Boundary<T>) {
+        self.boundary = boundary.sides();
+    }
+
+    /// Like `set_boundary`, with a different condition on each side
+    ///
+    /// A periodic side wraps around to the other end whatever that end is
+    /// set to, so it is meant to be used on both sides at once.
+    pub fn set_boundaries(&mut self, left: BoundaryKind<T>, right: BoundaryKind<T>) {
+        self.boundary = [left, right];
+    }
+
+    /// Builder form of `set_boundaries`, e.g. for a wall on the left and an
+    /// outflow on the right
+    pub fn with_boundaries(mut self, left: BoundaryKind<T>, right: BoundaryKind<T>) -> Self {
+        self.set_boundaries(left, right);
+        self
+    }
+
+    /// The left and right boundary conditions
+    pub fn boundaries(&self) -> [BoundaryKind<T>; 2] {
+        self.boundary
+    }
+
+    /// Extrapolate the ghost cells of the left and right boundary from the
+    /// interior instead, e.g. for an outflow boundary
+    ///
+    /// A side set to `None` keeps following `boundary`.
+    pub fn set_ghost_fill(&mut self, left: Option<GhostFill>, right: Option<GhostFill>) {
+        self.ghost_fill = [left, right];
+    }
+
+    /// Whether both ghost regions wrap around the domain
+    pub fn is_periodic(&self) -> bool {
+        self.boundary == [BoundaryKind::Periodic; 2] && self.ghost_fill == [None, None]
+    }
+
+    /// Discrete mass $\Delta x \sum_j u_j$, the midpoint rule over the cells
+    ///
+    /// A conservative scheme keeps it to rounding on a periodic domain, see
+    /// `is_periodic`. With Dirichlet values or ghost fills the mass changes by
+    /// the fluxes through the ends, as given by `boundary_flux`, and the held
+    /// cells of an obstacle are not conserved at all.
+    pub fn mass(&self) -> T {
+        self.state.iter().fold(T::from(0).unwrap(), |m, &u| m + u) * self.dx
+    }
+
+    /// Total variation $\sum_j |u_{j+1} - u_j|$ of the state
+    pub fn total_variation(&self) -> T {
+        self.state
+            .iter()
+            .zip(self.state.iter().skip(1))
+            .fold(T::from(0).unwrap(), |tv, (&l, &r)| tv + (r - l).abs())
+    }
+
+    /// Drift $M - M_0$ of the mass from the initial state
+    ///
+    /// See `relative_mass_error` for the initial mass $M_0$.
+    pub fn conservation_drift(&self) -> T {
+        self.mass() - self.initial_mass
+    }
+
+    /// Drift of the mass relative to the initial state
+    ///
+    /// $$
+    /// \frac{M - M_0}{M_0}
+    /// $$
+    ///
+    /// where $M_0$ is the mass at construction or at the last
+    /// `set_state_from_fn`. If $M_0 = 0$ the absolute drift $M - M_0$ is
+    /// returned instead.
+    pub fn relative_mass_error(&self) -> T {
+        let drift = self.conservation_drift();
+        if self.initial_mass == T::from(0).unwrap() {
+            drift
+        } else {
+            drift / self.initial_mass
         }
     }
 
+    /// Discrete $L^2$ energy $\frac{1}{2} \Delta x \sum_j u_j^2$
+    pub fn energy(&self) -> T {
+        self.energy_of(&self.state)
+    }
+
+    /// Rate of change of the energy from `previous` to the current state
+    ///
+    /// $$
+    /// \frac{E^{n+1} - E^{n}}{\Delta t}
+    /// $$
+    ///
+    /// which is negative for dissipative schemes and close to zero for
+    /// neutrally stable ones.
+    pub fn energy_dissipation_rate(&self, previous: &Array1<T>) -> T {
+        (self.energy() - self.energy_of(previous)) / self.dt
+    }
+
+    fn energy_of(&self, u: &Array1<T>) -> T {
+        let half = T::from(0.5).unwrap();
+        u.iter().fold(T::from(0).unwrap(), |e, &u| e + u * u) * self.dx * half
+    }
+
+    /// Check the discrete maximum principle from `previous` to the current state
+    ///
+    /// $$
+    /// \min_j u_j^{n} \le u_j^{n+1} \le \max_j u_j^{n}
+    /// $$
+    ///
+    /// Returns whether it held, together with the largest overshoot or
+    /// undershoot (zero if it held).
+    pub fn max_principle_check(&self, previous: &Array1<T>) -> (bool, T) {
+        let zero = T::from(0).unwrap();
+        let bounds = |u: &Array1<T>| {
+            u.iter().skip(1).fold((u[0], u[0]), |(min, max), &x| {
+                (if x < min { x } else { min }, if x > max { x } else { max })
+            })
+        };
+        let (min_prev, max_prev) = bounds(previous);
+        let (min, max) = bounds(&self.state);
+
+        let under = min_prev - min;
+        let over = max - max_prev;
+        let violation = if under > over { under } else { over };
+        if violation > zero {
+            (false, violation)
+        } else {
+            (true, zero)
+        }
+    }
+
+    /// The numerical fluxes $(h_{j-}, h_{j+})$ `scheme` would use this step,
+    /// e.g. to debug a custom scheme
+    pub fn interface_flux(
+        &self,
+        scheme: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+    ) -> Result<(Array1<T>, Array1<T>), SchemeError> {
+        let [h_neg, h_pos] = scheme.mixed_flux(self, eq)?;
+        Ok((h_neg, h_pos))
+    }
+
+    /// Mass $\Delta t \, h$ crossing the left and right boundary during the
+    /// next step of `scheme`, positive in the direction of $+x$
+    ///
+    /// For a conservative scheme the mass changes by exactly
+    /// `left - right` over the step, the discrete divergence theorem.
+    pub fn boundary_flux(
+        &self,
+        scheme: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+    ) -> Result<[T; 2], SchemeError> {
+        let (h_neg, h_pos) = self.interface_flux(scheme, eq)?;
+        Ok([self.dt * h_neg[0], self.dt * h_pos[self.len() - 1]])
+    }
+
+    /// Grid spacing $\Delta x$
+    pub fn dx(&self) -> T {
+        self.dx
+    }
+
+    /// Time step $\Delta t$
+    pub fn dt(&self) -> T {
+        self.dt
+    }
+
     pub fn dt_over_dx(&self) -> T {
         self.dt / self.dx
     }
 
+    /// Local CFL number $|f^{'}(u_j)| \frac{\Delta t}{\Delta x}$ of every cell
+    pub fn cfl_map(&self, eq: &dyn Equation<T>) -> Array1<T> {
+        let dt_over_dx = self.dt_over_dx();
+        eq.df_array(&self.state).mapv(|v| v.abs() * dt_over_dx)
+    }
+
+    /// One step of `scheme` backward in time, i.e. with $-\Delta t$
+    ///
+    /// Non-dissipative schemes approximately retrace a forward run, while
+    /// dissipative ones like the upwind keep smearing the solution instead,
+    /// showing that they are not time-reversible.
+    ///
+    /// Returns the time reached, or the error of `step`, after which
+    /// neither the state nor the time step change.
+    pub fn step_backward(
+        &mut self,
+        scheme: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+    ) -> Result<T, SchemeError> {
+        self.dt = -self.dt;
+        let stepped = self.step(scheme, eq);
+        self.dt = -self.dt;
+        stepped
+    }
+
+    /// A copy holding everything a stage residual reads, i.e. without the
+    /// history and the spare state
+    ///
+    /// A multi-stage integrator steps this copy instead of a full `clone`,
+    /// which would copy the whole history on every step.
+    pub(crate) fn stage(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            dt: self.dt,
+            dx: self.dx,
+            t: self.t,
+            grid: self.grid.clone(),
+            boundary: self.boundary,
+            ghost_fill: self.ghost_fill,
+            obstacle: self.obstacle.clone(),
+            initial_mass: self.initial_mass,
+            fallback_dt: self.fallback_dt,
+            equation: self.equation.clone(),
+            spare_state: Array1::from(vec![]),
+            history: None,
+        }
+    }
+
+    /// Stage update $u + \text{factor} \cdot \text{rhs}$ of the current state
+    ///
+    /// The building block of Runge-Kutta stages; the state itself is left
+    /// untouched, so several stages can be combined before `set_state`. With
+    /// `CheckMode::Warn` an `rhs` of the wrong length is dropped, returning
+    /// the state as it is.
+    pub fn rk_substep(&self, rhs: &Array1<T>, factor: T) -> Array1<T> {
+        if !sanity_check_eq!(self.len(), rhs.len()) {
+            return self.state.clone();
+        }
+        Array1::from_shape_fn(self.len(), |j| self.state[j] + factor * rhs[j])
+    }
+
+    /// Dispersive part $\Delta t \mu \delta^3 u_j$ of an update, if `eq` has one
+    ///
+    /// $$
+    /// \delta^3 u_j = \frac{u_{j+2} - 2u_{j+1} + 2u_{j-1} - u_{j-2}}{2 \Delta x^3}
+    /// $$
+    pub fn dispersive_update(&self, eq: &dyn Equation<T>) -> Option<Array1<T>> {
+        let mu = eq.dispersion()?;
+
+        // extended u: [n+4]
+        let u = self.get_u(2);
+        let two = T::from(2).unwrap();
+        let factor = self.dt * mu / (two * self.dx * self.dx * self.dx);
+        let update = Array1::from_shape_fn(self.len(), |j| {
+            // u_{j-2}, u_{j-1}, u_{j+1}, u_{j+2}
+            let (u1, u2, u4, u5) = (u[j], u[j + 1], u[j + 3], u[j + 4]);
+            factor * (u5 - two * u4 + two * u2 - u1)
+        });
+        Some(update)
+    }
+
+    /// Diffusive part $\Delta t \nu \delta^2 u_j$ of an update, if `eq` has one
+    ///
+    /// $$
+    /// \delta^2 u_j = \frac{u_{j+1} - 2u_j + u_{j-1}}{\Delta x^2}
+    /// $$
+    pub fn diffusive_update(&self, eq: &dyn Equation<T>) -> Option<Array1<T>> {
+        let nu = eq.viscosity()?;
+
+        // extended u: [n+2]
+        let u = self.get_u(1);
+        let two = T::from(2).unwrap();
+        let factor = self.dt * nu / (self.dx * self.dx);
+        let update = Array1::from_shape_fn(self.len(), |j| {
+            // u_{j-1}, u_j, u_{j+1}
+            let (u0, u1, u2) = (u[j], u[j + 1], u[j + 2]);
+            factor * (u2 - two * u1 + u0)
+        });
+        Some(update)
+    }
+
     // get discrete u
     pub fn get_u(&self, ext: usize) -> Array1<T> {
-        let u = if ext > 0 {
-            let u = &self.state;
-            let mut v: Vec<T> = u.to_vec();
-
-            for i in 0..ext {
-                // left boundary
-                v.insert(
-                    0,
-                    match self.boundary {
-                        Some(b) => b[0],            // left source
-                        None => u[u.len() - 1 - i], // loop to the right
-                    },
-                );
-
-                // right boundary
-                v.push(match self.boundary {
-                    Some(b) => b[1], // right source
-                    None => u[i],    // loop to the left
-                });
-            }
-
-            Array1::<T>::from(v)
-        } else {
-            self.state.clone()
-        };
+        let mut v = vec![];
+        self.get_u_into(ext, &mut v);
+        let u = Array1::<T>::from(v);
 
         // sanity check
-        assert_eq!(self.len() + 2 * ext, u.len());
+        debug_sanity_check_eq!(self.len() + 2 * ext, u.len());
         u
     }
 
+    /// Like `get_u`, but filled into the scratch buffer `out` of the caller
+    ///
+    /// `out` is resized to $n + 2 \cdot ext$, so once it has grown to the
+    /// widest `ext` used, repeated calls allocate nothing.
+    pub fn get_u_into(&self, ext: usize, out: &mut Vec<T>) {
+        out.clear();
+        if ext == 0 {
+            out.extend(self.state.iter().cloned());
+        } else {
+            out.resize(self.len() + 2 * ext, T::from(0).unwrap());
+            self.fill_u(ext, out);
+        }
+    }
+
+    // the state with `ext` ghost cells on each side, into `out` of length n + 2 * ext
+    fn fill_u(&self, ext: usize, out: &mut [T]) {
+        let u = &self.state;
+        let n = u.len();
+        out[ext..ext + n]
+            .iter_mut()
+            .zip(u.iter())
+            .for_each(|(o, &u)| *o = u);
+
+        // interior values ordered from each boundary inward, repeating the
+        // innermost one on grids shorter than the widest ghost fill
+        let inner = |i: usize| i.min(n - 1);
+        let left_inner = [u[inner(0)], u[inner(1)], u[inner(2)]];
+        let right_inner = [
+            u[n - 1 - inner(0)],
+            u[n - 1 - inner(1)],
+            u[n - 1 - inner(2)],
+        ];
+
+        for i in 0..ext {
+            // left boundary
+            out[ext - 1 - i] = match (self.ghost_fill[0], self.boundary[0]) {
+                (Some(fill), _) => fill.extrapolate(&left_inner, i + 1), // extrapolate
+                (None, BoundaryKind::Dirichlet(b)) => b,                 // left source
+                (None, BoundaryKind::Neumann) => u[0],                   // zero gradient
+                (None, BoundaryKind::Reflective) => u[inner(i)],         // mirror
+                (None, BoundaryKind::Periodic) => u[n - 1 - i],          // loop to the right
+            };
+
+            // right boundary
+            out[ext + n + i] = match (self.ghost_fill[1], self.boundary[1]) {
+                (Some(fill), _) => fill.extrapolate(&right_inner, i + 1), // extrapolate
+                (None, BoundaryKind::Dirichlet(b)) => b,                  // right source
+                (None, BoundaryKind::Neumann) => u[n - 1],                // zero gradient
+                (None, BoundaryKind::Reflective) => u[n - 1 - inner(i)],  // mirror
+                (None, BoundaryKind::Periodic) => u[i],                   // loop to the left
+            };
+        }
+    }
+
     // get discrete f
     pub fn get_f(&self, eq: &dyn Equation<T>, ext: usize) -> Array1<T> {
-        let f = self.get_u(ext).map(|x| eq.f(*x));
+        let f = eq.f_array(&self.get_u(ext));
 
         // sanity check
-        assert_eq!(self.len() + 2 * ext, f.len());
+        debug_sanity_check_eq!(self.len() + 2 * ext, f.len());
         f
     }
 
@@ -116,7 +832,7 @@ where
         // Convert to f64 since gnuplot only support this
         let grid: Array1<f64> = self.grid.map(|x| x.to_f64().unwrap());
         let state: Array1<f64> = self.state.map(|x| x.to_f64().unwrap());
-        fg.set_title(&name).set_offset(2.0, 0.0);
+        fg.set_title(name).set_offset(2.0, 0.0);
         fg.axes2d()
             // .set_aspect_ratio(AutoOption::Fix(0.5))
             // .set_size(0.6, 0.4)
@@ -126,3 +842,468 @@ where
         fg.show().unwrap();
     }
 }
+
+/// Nodes $a, a + \Delta x, \dots$ below $b$, the same half-open grid as
+/// `Array::range` without requiring `Float`
+pub(crate) fn half_open_grid<T: Numeric>(dx: T, range: [T; 2]) -> Array1<T> {
+    let n = ((range[1] - range[0]) / dx).to_f64().unwrap().ceil() as usize;
+    Array1::from_shape_fn(n, |i| range[0] + dx * T::from(i).unwrap())
+}
+
+impl<T> Simluation<T>
+where
+    T: Float,
+{
+    /// Like `new`, but derive the time step from a target CFL number by
+    /// `stable_dt` of the initial state
+    ///
+    /// `fallback_dt` is used if the initial state has no wave speed at all,
+    /// e.g. Burgers starting at rest, see `set_fallback_dt`.
+    pub fn with_cfl<F>(
+        dx: T,
+        cfl: T,
+        range: [T; 2],
+        init: F,
+        eq: &dyn Equation<T>,
+        fallback_dt: T,
+    ) -> Self
+    where
+        F: Fn(T) -> T,
+    {
+        let mut sim = Self::new(dx, fallback_dt, range, init);
+        sim.set_fallback_dt(fallback_dt);
+        sim.dt = sim.stable_dt(eq, cfl);
+        sim
+    }
+
+    /// Time step returned by `stable_dt` when the state has no wave speed
+    /// at all, where the CFL condition does not bound $\Delta t$
+    pub fn set_fallback_dt(&mut self, dt: T) {
+        self.fallback_dt = Some(dt);
+    }
+
+    /// Time step reaching the CFL number `cfl` on the current state
+    ///
+    /// $$
+    /// \Delta t = \text{cfl} \frac{\Delta x}{\max_j |f^{'}(u_j)|}
+    /// $$
+    ///
+    /// If the state has no wave speed at all, it falls back to the time step
+    /// of `set_fallback_dt`, or to unit speed, i.e. `cfl * dx`, if none was
+    /// given.
+    pub fn stable_dt(&self, eq: &dyn Equation<T>, cfl: T) -> T {
+        let max_speed = eq
+            .df_array(&self.state)
+            .iter()
+            .fold(T::from(0).unwrap(), |m, v| m.max(v.abs()));
+        if max_speed > T::from(0).unwrap() {
+            cfl * self.dx / max_speed
+        } else {
+            self.fallback_dt.unwrap_or(cfl * self.dx)
+        }
+    }
+
+    /// Run every pre-flight check at once, collecting all problems found
+    ///
+    /// - `dx` and `dt` are positive
+    /// - the initial state satisfies the CFL condition
+    /// - the state and the grid have the same length
+    /// - the flux is linear if `scheme` requires so
+    /// - the boundary values are finite and the grid is long enough for the
+    ///   ghost fill
+    pub fn validate(
+        &self,
+        scheme: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+    ) -> Result<(), Vec<FdError>> {
+        let zero = T::from(0).unwrap();
+        let mut errors = vec![];
+
+        if self.dx <= zero || self.dx.is_nan() {
+            errors.push(FdError::NonPositiveDx(self.dx.to_f64().unwrap()));
+        }
+        if self.dt <= zero || self.dt.is_nan() {
+            errors.push(FdError::NonPositiveDt(self.dt.to_f64().unwrap()));
+        }
+        if self.state.len() != self.grid.len() {
+            errors.push(FdError::LengthMismatch {
+                grid: self.grid.len(),
+                state: self.state.len(),
+            });
+        }
+
+        let speeds: Vec<T> = self.state.iter().map(|&u| eq.df(u)).collect();
+        let max_speed = speeds.iter().fold(zero, |m, v| m.max(v.abs()));
+        let cfl = (max_speed * self.dt / self.dx).abs();
+        if cfl > T::from(1).unwrap() {
+            errors.push(FdError::CflViolated(cfl.to_f64().unwrap()));
+        }
+        if scheme.describe().requires_linear_flux && speeds.iter().any(|&v| v != speeds[0]) {
+            errors.push(FdError::NonlinearFlux);
+        }
+
+        for side in self.boundary.iter() {
+            if let BoundaryKind::Dirichlet(b) = side {
+                if !b.is_finite() {
+                    errors.push(FdError::InvalidBoundary(b.to_f64().unwrap()));
+                }
+            }
+        }
+        let needed = self.ghost_fill.iter().flatten().map(GhostFill::width).max();
+        if let Some(needed) = needed.filter(|&needed| needed > self.len()) {
+            errors.push(FdError::GridTooShort {
+                needed,
+                len: self.len(),
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Whether both grids and both states agree pointwise within `tol`
+    pub fn approx_eq(&self, other: &Simluation<T>, tol: T) -> bool {
+        let close = |a: &Array1<T>, b: &Array1<T>| {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(&a, &b)| (a - b).abs() <= tol)
+        };
+        close(&self.grid, &other.grid) && close(&self.state, &other.state)
+    }
+
+    /// Index of the grid point nearest to `x`
+    ///
+    /// Returns `None` if `x` lies more than half a cell outside the grid.
+    pub fn grid_index_of(&self, x: T) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        let half = T::from(0.5).unwrap();
+        let j = (x - self.grid[0]) / self.dx;
+        if j < -half || j > T::from(self.len() - 1).unwrap() + half {
+            return None;
+        }
+        j.round().to_usize().map(|j| j.min(self.len() - 1))
+    }
+
+    /// Index of the cell $[x_j, x_j + \Delta x)$ containing `x`
+    ///
+    /// Returns `None` if `x` lies outside the cells spanned by the grid.
+    pub fn cell_containing(&self, x: T) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        let j = (x - self.grid[0]) / self.dx;
+        if j < T::from(0).unwrap() {
+            return None;
+        }
+        j.floor().to_usize().filter(|&j| j < self.len())
+    }
+
+    /// Indices of the grid points within $[a, b]$, for `state_slice` and
+    /// `grid_slice`
+    ///
+    /// The range is empty if no grid point lies in between.
+    pub fn index_range(&self, [a, b]: [T; 2]) -> Range<usize> {
+        let start = self.grid.iter().take_while(|&&x| x < a).count();
+        let end = start
+            + self
+                .grid
+                .iter()
+                .skip(start)
+                .take_while(|&&x| x <= b)
+                .count();
+        start..end
+    }
+
+    /// Linearly interpolated state at `x`
+    ///
+    /// Returns `None` outside $[x_0, x_{n-1}]$.
+    pub fn sample_at(&self, x: T) -> Option<T> {
+        let last = self.len().checked_sub(1)?;
+        if x < self.grid[0] || x > self.grid[last] {
+            return None;
+        }
+        if last == 0 {
+            return Some(self.state[0]);
+        }
+        // x_{n-1} itself is interpolated within the last interior cell
+        let j = self.cell_containing(x)?.min(last - 1);
+        let w = (x - self.grid[j]) / self.dx;
+        Some(self.state[j] + w * (self.state[j + 1] - self.state[j]))
+    }
+
+    /// Per-cell difference `self.state - reference.state`, e.g. between a
+    /// coarse and a fine run or two schemes
+    ///
+    /// On a different grid the reference is resampled with `sample_at` at the
+    /// cells of `self`, giving NaN where they lie outside of it.
+    pub fn compare_to(&self, reference: &Simluation<T>) -> Array1<T> {
+        if self.grid == reference.grid {
+            return &self.state - &reference.state;
+        }
+        Array1::from_shape_fn(self.len(), |j| {
+            let u = reference.sample_at(self.grid[j]).unwrap_or_else(T::nan);
+            self.state[j] - u
+        })
+    }
+
+    /// Coordinate of the steepest gradient, provided it exceeds `threshold`
+    ///
+    /// The position is reported at the interface $x_j + \Delta x / 2$ between
+    /// the two cells with the largest $|u_{j+1} - u_j| / \Delta x$.
+    pub fn shock_position(&self, threshold: T) -> Option<T> {
+        let (j, gradient) = self
+            .state
+            .iter()
+            .zip(self.state.iter().skip(1))
+            .map(|(&l, &r)| ((r - l) / self.dx).abs())
+            .enumerate()
+            .fold(None, |max: Option<(usize, T)>, (j, g)| match max {
+                Some((_, g_max)) if g_max >= g => max,
+                _ => Some((j, g)),
+            })?;
+        if gradient > threshold {
+            Some(self.grid[j] + self.dx / T::from(2).unwrap())
+        } else {
+            None
+        }
+    }
+
+    /// Largest pointwise difference $\max_j |u_j^a - u_j^b|$ between one
+    /// step of scheme `a` and one of scheme `b` from the current state
+    pub fn scheme_divergence(
+        &self,
+        a: &dyn Scheme<T>,
+        b: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+    ) -> T {
+        let u_a = a.run_unchecked(self, eq);
+        let u_b = b.run_unchecked(self, eq);
+        u_a.iter()
+            .zip(u_b.iter())
+            .map(|(&a, &b)| (a - b).abs())
+            .fold(T::from(0).unwrap(), T::max)
+    }
+
+    /// Estimated time until the feature at the steepest gradient leaves the
+    /// domain
+    ///
+    /// $$
+    /// t = \frac{\min(x - x_L, x_R - x)}{\max_j |f^{'}(u_j)|}
+    /// $$
+    ///
+    /// where $x$ is the `shock_position` and $[x_L, x_R)$ the domain. Returns
+    /// `None` if there is no gradient to follow or no wave speed at all.
+    pub fn time_to_exit(&self, eq: &dyn Equation<T>) -> Option<T> {
+        let zero = T::from(0).unwrap();
+        let x = self.shock_position(zero)?;
+        let max_speed = self
+            .state
+            .iter()
+            .map(|&u| eq.df(u).abs())
+            .fold(zero, T::max);
+        if max_speed == zero {
+            return None;
+        }
+        let left = self.grid[0];
+        let right = left + T::from(self.len()).unwrap() * self.dx;
+        Some((x - left).min(right - x) / max_speed)
+    }
+
+    /// Characteristics $x(t) = x_0 + f^{'}(u(x_0)) t$ of the current state from
+    /// each of `starts`, as `(t, x)` at `samples + 1` times evenly spanning
+    /// `duration`
+    ///
+    /// The state is constant along each of them, so they are straight lines
+    /// of slope $dt/dx = 1 / f^{'}(u)$, e.g. $1 / a$ for advection and $1 / u$
+    /// for Burgers. Where they cross a shock forms, past which they are no
+    /// longer valid. Starts outside the grid are left out.
+    pub fn export_characteristics(
+        &self,
+        eq: &dyn Equation<T>,
+        starts: &[T],
+        duration: T,
+        samples: usize,
+    ) -> Vec<Vec<(T, T)>> {
+        let samples = samples.max(1);
+        let dt = duration / T::from(samples).unwrap();
+        starts
+            .iter()
+            .filter_map(|&x0| {
+                let speed = eq.df(self.sample_at(x0)?);
+                let curve = (0..=samples)
+                    .map(|i| {
+                        let t = dt * T::from(i).unwrap();
+                        (t, x0 + speed * t)
+                    })
+                    .collect();
+                Some(curve)
+            })
+            .collect()
+    }
+
+    /// Per-cell local truncation error estimated by step doubling
+    ///
+    /// $$
+    /// \tau_j = |u_j^{\Delta t} - u_j^{\Delta t / 2, \Delta t / 2}|
+    /// $$
+    ///
+    /// i.e. the difference between one step of `dt` and two steps of `dt / 2`
+    /// from the current state, which is left untouched. It is small in smooth
+    /// regions and largest where the scheme is least accurate, e.g. at shocks.
+    pub fn local_truncation_error(
+        &self,
+        scheme: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+    ) -> Array1<T> {
+        let full = scheme.run_unchecked(self, eq);
+
+        let mut half = self.clone();
+        half.dt = self.dt / T::from(2).unwrap();
+        for _ in 0..2 {
+            half.set_state(scheme.run_unchecked(&half, eq));
+        }
+
+        (full - half.state).mapv(T::abs)
+    }
+
+    /// Advance `steps` time steps by `step`, recording the shock trajectory
+    /// as `(t, x)`
+    ///
+    /// Steps where no gradient exceeds `threshold` are left out. Fails with
+    /// the first error of `step`, e.g. a CFL violation.
+    pub fn track_shock(
+        &mut self,
+        scheme: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+        threshold: T,
+        steps: usize,
+    ) -> Result<Vec<(T, T)>, SchemeError> {
+        let mut trajectory = vec![];
+        for _ in 0..steps {
+            let t = self.step(scheme, eq)?;
+            if let Some(x) = self.shock_position(threshold) {
+                trajectory.push((t, x));
+            }
+        }
+        Ok(trajectory)
+    }
+
+    /// Cells flagged by the Jameson sensor
+    ///
+    /// $$
+    /// s_j = \frac{|u_{j+1} - 2u_j + u_{j-1}|}{|u_{j+1}| + 2|u_j| + |u_{j-1}|} > \text{threshold},
+    /// $$
+    ///
+    /// which is $O(\Delta x^2)$ in smooth regions away from $u = 0$ and $O(1)$
+    /// at a jump.
+    pub fn shock_sensor(&self, threshold: T) -> Vec<bool> {
+        let zero = T::from(0).unwrap();
+        let two = T::from(2).unwrap();
+        // extended u: [n+2]
+        let u = self.get_u(1);
+        u.windows(3)
+            .into_iter()
+            .map(|w| {
+                let scale = w[0].abs() + two * w[1].abs() + w[2].abs();
+                scale > zero && (w[2] - two * w[1] + w[0]).abs() / scale > threshold
+            })
+            .collect()
+    }
+
+    /// Add the artificial viscosity $\nu (u_{j+1} - 2u_j + u_{j-1})$, i.e. a
+    /// $\Delta x^2$-scaled Laplacian, near the cells flagged by `shock_sensor`
+    ///
+    /// The viscous flux $\nu (u_{j+1} - u_j)$ is only switched on at the
+    /// interfaces of flagged cells, which keeps the update conservative up to
+    /// the boundary fluxes and leaves cells away from a jump untouched. Stability requires
+    /// $\nu \le 1/2$. Returns the number of flagged cells.
+    pub fn apply_artificial_viscosity(&mut self, nu: T, threshold: T) -> usize {
+        let n = self.len();
+        let zero = T::from(0).unwrap();
+        let flagged = self.shock_sensor(threshold);
+
+        // extended u: [n+2]
+        let u = self.get_u(1);
+        // on a periodic domain the first and last interface coincide
+        let wrap = self.is_periodic() && (flagged[0] || flagged[n - 1]);
+
+        // viscous flux at interface k between u_{k-1} and u_k: [n+1]
+        let flux: Vec<T> = (0..=n)
+            .map(|k| {
+                let edge = (k == 0 || k == n) && wrap;
+                let active = edge || (k > 0 && flagged[k - 1]) || (k < n && flagged[k]);
+                if active {
+                    nu * (u[k + 1] - u[k])
+                } else {
+                    zero
+                }
+            })
+            .collect();
+
+        let state = Array1::from_shape_fn(n, |j| {
+            if flux[j + 1] == zero && flux[j] == zero {
+                self.state[j]
+            } else {
+                self.state[j] + (flux[j + 1] - flux[j])
+            }
+        });
+        self.set_state(state);
+        flagged.iter().filter(|&&f| f).count()
+    }
+
+    /// Restore the mass of `reference` after a non-conservative limiter or
+    /// clamp, keeping every cell within `bounds`
+    ///
+    /// Each cell takes back its change $r_j - u_j$ as far as `bounds` allow,
+    /// and passes the remainder on as a flux through its right interface, then
+    /// a backward sweep places what reached the last cell. Unlike a uniform
+    /// shift the correction stays local to the limited cells. Returns the mass
+    /// that found no room, which is zero unless `bounds` cannot hold the mass
+    /// of `reference`. With `CheckMode::Warn` a `reference` of the wrong
+    /// length leaves the state untouched.
+    pub fn flux_limited_mass_fix(&mut self, reference: &Array1<T>, bounds: [T; 2]) -> T {
+        let zero = T::from(0).unwrap();
+        if !sanity_check_eq!(self.len(), reference.len()) {
+            return zero;
+        }
+        let [lo, hi] = bounds;
+        let mut u = self.state.clone();
+
+        // deposit as much of `carry` into u_j as its bounds allow
+        let deposit = |u: &mut T, carry: T| {
+            let target = (*u + carry).max(lo).min(hi);
+            let placed = target - *u;
+            *u = target;
+            carry - placed
+        };
+
+        let mut carry = zero;
+        for j in 0..self.len() {
+            carry = deposit(&mut u[j], carry + reference[j] - self.state[j]);
+        }
+        for j in (0..self.len()).rev() {
+            carry = deposit(&mut u[j], carry);
+        }
+
+        self.set_state(u);
+        carry * self.dx
+    }
+}
+
+impl<T> Simluation<T>
+where
+    T: AsF64,
+{
+    /// The grid as `f64`, borrowed without conversion if `T` is `f64`
+    pub fn grid_view_f64(&self) -> CowArray<'_, f64, Ix1> {
+        T::as_f64_array(&self.grid)
+    }
+
+    /// The state as `f64`, borrowed without conversion if `T` is `f64`
+    pub fn state_view_f64(&self) -> CowArray<'_, f64, Ix1> {
+        T::as_f64_array(&self.state)
+    }
+}
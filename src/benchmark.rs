@@ -0,0 +1,246 @@
+//! # Scheme Benchmark
+//!
+//! Head-to-head comparison of schemes on a validation problem with a known
+//! exact solution, reporting error norms and timing.
+
+use crate::base::{Equation, Simluation, StepMode};
+use crate::error::SchemeError;
+use crate::schemes::Scheme;
+use num_traits::Float;
+use std::cmp::Ordering;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// A problem with exact solution `exact(x, t)`, solved by `steps` time steps
+pub struct ValidationProblem<'a, T> {
+    pub equation: &'a dyn Equation<T>,
+    pub init: &'a dyn Fn(T) -> T,
+    pub exact: &'a dyn Fn(T, T) -> T,
+    pub dx: T,
+    pub dt: T,
+    pub range: [T; 2],
+    pub steps: usize,
+}
+
+/// Discrete error norms against an exact solution
+///
+/// $$
+/// L^1 = \Delta x \sum_j |e_j|, \quad
+/// L^2 = \sqrt{\Delta x \sum_j e_j^2}, \quad
+/// L^\infty = \max_j |e_j|
+/// $$
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ErrorNorms<T> {
+    pub l1: T,
+    pub l2: T,
+    pub linf: T,
+}
+
+impl<T> Simluation<T>
+where
+    T: Float,
+{
+    /// Error norms of the state against `exact(x)` sampled on the grid
+    pub fn error_norms(&self, exact: &dyn Fn(T) -> T) -> ErrorNorms<T> {
+        let zero = T::from(0).unwrap();
+        let (l1, l2, linf) = self
+            .grid
+            .iter()
+            .zip(self.state.iter())
+            .map(|(&x, &u)| (u - exact(x)).abs())
+            .fold((zero, zero, zero), |(l1, l2, linf), e| {
+                (l1 + e, l2 + e * e, linf.max(e))
+            });
+        ErrorNorms {
+            l1: l1 * self.dx,
+            l2: (l2 * self.dx).sqrt(),
+            linf,
+        }
+    }
+}
+
+/// Exact solution $u(x, t)$ of a validation problem
+pub trait ExactSolution<T> {
+    fn eval(&self, x: T, t: T) -> T;
+}
+
+/// Exact solution of periodic advection, the initial profile shifted by $a t$
+///
+/// $$
+/// u(x, t) = u_0(x - a t)
+/// $$
+///
+/// with $x - a t$ wrapped back into `range`, the period of the domain.
+pub struct PeriodicAdvection<T, F> {
+    pub a: T,
+    pub range: [T; 2],
+    pub init: F,
+}
+
+impl<T, F> ExactSolution<T> for PeriodicAdvection<T, F>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    fn eval(&self, x: T, t: T) -> T {
+        let period = self.range[1] - self.range[0];
+        let s = (x - self.a * t - self.range[0]) / period;
+        (self.init)(self.range[0] + (s - s.floor()) * period)
+    }
+}
+
+/// Error norms of the state of `sim` against `exact` at time `t`
+pub fn error_norms<T>(sim: &Simluation<T>, exact: &dyn ExactSolution<T>, t: T) -> ErrorNorms<T>
+where
+    T: Float,
+{
+    sim.error_norms(&|x| exact.eval(x, t))
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkEntry {
+    pub name: String,
+    /// Error norms at the end of the run, or the error the scheme stopped at
+    pub errors: Result<ErrorNorms<f64>, SchemeError>,
+    /// Time spent stepping, up to the error if any
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkReport {
+    pub entries: Vec<BenchmarkEntry>,
+}
+
+impl BenchmarkReport {
+    /// Order the entries from the smallest $L^2$ error, the failed runs last
+    pub fn sort_by_accuracy(&mut self) {
+        self.entries.sort_by(|a, b| match (&a.errors, &b.errors) {
+            (Ok(a), Ok(b)) => a.l2.partial_cmp(&b.l2).unwrap_or(Ordering::Equal),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => Ordering::Equal,
+        });
+    }
+
+    /// Order the entries from the fastest
+    pub fn sort_by_speed(&mut self) {
+        self.entries.sort_by_key(|entry| entry.elapsed);
+    }
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<24} {:>12} {:>12} {:>12} {:>12}",
+            "scheme", "L1", "L2", "Linf", "time [ms]"
+        )?;
+        for entry in self.entries.iter() {
+            match &entry.errors {
+                Ok(errors) => writeln!(
+                    f,
+                    "{:<24} {:>12.4e} {:>12.4e} {:>12.4e} {:>12.3}",
+                    entry.name,
+                    errors.l1,
+                    errors.l2,
+                    errors.linf,
+                    entry.elapsed.as_secs_f64() * 1e3
+                )?,
+                Err(e) => writeln!(f, "{:<24} failed: {}", entry.name, e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Solve `problem` with each of `schemes`, in the given order
+///
+/// A scheme failing a `step`, e.g. on a CFL violation, gets its error
+/// recorded in its entry while the other schemes still run.
+pub fn benchmark<T>(schemes: &[&dyn Scheme<T>], problem: &ValidationProblem<T>) -> BenchmarkReport
+where
+    T: Float,
+{
+    let entries = schemes
+        .iter()
+        .map(|scheme| {
+            let mut sim = Simluation::new(problem.dx, problem.dt, problem.range, problem.init);
+            let start = Instant::now();
+            let run = (0..problem.steps).try_for_each(|_| {
+                sim.step(*scheme, problem.equation)?;
+                Ok(())
+            });
+            let elapsed = start.elapsed();
+
+            let errors = run.map(|()| {
+                let t = sim.time();
+                let errors = sim.error_norms(&|x| (problem.exact)(x, t));
+                ErrorNorms {
+                    l1: errors.l1.to_f64().unwrap(),
+                    l2: errors.l2.to_f64().unwrap(),
+                    linf: errors.linf.to_f64().unwrap(),
+                }
+            });
+            BenchmarkEntry {
+                name: scheme.describe().name,
+                errors,
+                elapsed,
+            }
+        })
+        .collect();
+    BenchmarkReport { entries }
+}
+
+/// $L^1$ errors over a sequence of grids with the fitted order of accuracy
+#[derive(Debug, Clone)]
+pub struct ConvergenceStudy {
+    /// $(\Delta x, L^1)$ of each resolution, in the given order
+    pub samples: Vec<(f64, f64)>,
+    /// Least-squares slope of $\log L^1$ against $\log \Delta x$
+    pub order: f64,
+}
+
+/// Solve up to `t_end` on `range` split into each of `resolutions` cells,
+/// starting from `exact` at $t = 0$
+///
+/// The ratio $\Delta t / \Delta x$ is held at `cfl` on every grid, so only the
+/// resolution changes between the runs.
+pub fn convergence_study<T>(
+    scheme: &dyn Scheme<T>,
+    eq: &dyn Equation<T>,
+    exact: &dyn ExactSolution<T>,
+    range: [T; 2],
+    cfl: T,
+    resolutions: &[usize],
+    t_end: T,
+) -> Result<ConvergenceStudy, SchemeError>
+where
+    T: Float,
+{
+    let zero = T::from(0).unwrap();
+    let samples = resolutions
+        .iter()
+        .map(|&n| {
+            let dx = (range[1] - range[0]) / T::from(n).unwrap();
+            let mut sim = Simluation::new(dx, cfl * dx, range, |x| exact.eval(x, zero));
+            sim.run_until(t_end, scheme, eq, StepMode::Fixed)?;
+            let l1 = error_norms(&sim, exact, t_end).l1;
+            Ok((dx.to_f64().unwrap(), l1.to_f64().unwrap()))
+        })
+        .collect::<Result<Vec<_>, SchemeError>>()?;
+
+    let points: Vec<(f64, f64)> = samples.iter().map(|&(dx, l1)| (dx.ln(), l1.ln())).collect();
+    let len = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / len;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / len;
+    let (sxy, sxx) = points.iter().fold((0., 0.), |(sxy, sxx), &(x, y)| {
+        (
+            sxy + (x - mean_x) * (y - mean_y),
+            sxx + (x - mean_x).powi(2),
+        )
+    });
+    Ok(ConvergenceStudy {
+        samples,
+        order: sxy / sxx,
+    })
+}
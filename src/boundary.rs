@@ -0,0 +1,100 @@
+//! # Boundary
+//!
+//! Helpers for filling the ghost cells at the edges of the domain.
+
+use crate::numeric::Numeric;
+
+/// ## Boundary condition
+///
+/// How the ghost cells beyond one end of the domain are filled by `get_u`,
+/// unless a [`GhostFill`] takes over that side.
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BoundaryKind<T> {
+    /// The ghost cells wrap around to the other end of the domain
+    Periodic,
+    /// A fixed source value
+    Dirichlet(T),
+    /// Zero gradient, every ghost layer copies the nearest interior value
+    /// so that waves leave the domain
+    Neumann,
+    /// Solid wall, the ghost layers mirror the interior, $u_{-i} = u_{i-1}$
+    Reflective,
+}
+
+/// The same kind of boundary condition on both ends
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Boundary<T> {
+    Periodic,
+    /// Fixed source values `[left, right]`
+    Dirichlet([T; 2]),
+    Neumann,
+    Reflective,
+}
+
+impl<T> Boundary<T> {
+    /// The left and right [`BoundaryKind`]
+    pub fn sides(self) -> [BoundaryKind<T>; 2] {
+        match self {
+            Boundary::Periodic => [BoundaryKind::Periodic, BoundaryKind::Periodic],
+            Boundary::Dirichlet([left, right]) => [
+                BoundaryKind::Dirichlet(left),
+                BoundaryKind::Dirichlet(right),
+            ],
+            Boundary::Neumann => [BoundaryKind::Neumann, BoundaryKind::Neumann],
+            Boundary::Reflective => [BoundaryKind::Reflective, BoundaryKind::Reflective],
+        }
+    }
+}
+
+/// ## Ghost cell extrapolation
+///
+/// At an outflow boundary the ghost cells are extrapolated from the interior.
+/// Writing $u_0, u_1, u_2$ for the interior values moving inward from the
+/// boundary, the ghost layer $i = 1, 2, \dots$ outside is filled by
+///
+/// $$
+/// u_{-i} = \begin{cases}
+/// u_0,& \text{Constant} \\\\
+/// (1 + i) u_0 - i u_1,& \text{Linear} \\\\
+/// \frac{(i+1)(i+2)}{2} u_0 - i(i+2) u_1 + \frac{i(i+1)}{2} u_2,& \text{Quadratic}
+/// \end{cases}
+/// $$
+///
+/// i.e. the polynomial through the innermost one, two or three cells.
+/// Wide stencils need the higher orders to keep their accuracy near an
+/// outflow boundary.
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GhostFill {
+    Constant,
+    Linear,
+    Quadratic,
+}
+
+impl GhostFill {
+    /// Number of interior cells the extrapolation reads
+    pub fn width(&self) -> usize {
+        match self {
+            GhostFill::Constant => 1,
+            GhostFill::Linear => 2,
+            GhostFill::Quadratic => 3,
+        }
+    }
+
+    /// Value of ghost `layer` (1 is adjacent to the boundary), given the
+    /// interior values ordered from the boundary inward
+    pub fn extrapolate<T: Numeric>(&self, inner: &[T], layer: usize) -> T {
+        let c = |x: usize| T::from(x).unwrap();
+        let i = layer;
+        match self {
+            GhostFill::Constant => inner[0],
+            GhostFill::Linear => c(1 + i) * inner[0] - c(i) * inner[1],
+            GhostFill::Quadratic => {
+                let two = c(2);
+                c((i + 1) * (i + 2)) / two * inner[0] - c(i * (i + 2)) * inner[1]
+                    + c(i * (i + 1)) / two * inner[2]
+            }
+        }
+    }
+}
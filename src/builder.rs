@@ -0,0 +1,149 @@
+//! # Simulation Builder
+//!
+//! Named setters for the setup of a [`Simluation`], checked once by
+//! `build` instead of failing mid-run.
+//!
+//! ```
+//! use fdm::boundary::Boundary;
+//! use fdm::builder::SimulationBuilder;
+//! use fdm::equations::InviscidBurger;
+//!
+//! let sim = SimulationBuilder::new()
+//!     .range([-1., 1.])
+//!     .dx(0.01)
+//!     .cfl(0.8)
+//!     .equation(Box::new(InviscidBurger))
+//!     .boundary(Boundary::Neumann)
+//!     .init(|x: f64| if x < 0. { 1. } else { 0. })
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(sim.len(), 200);
+//! ```
+
+use crate::base::{Equation, Simluation};
+use crate::boundary::{Boundary, BoundaryKind};
+use crate::error::BuildError;
+use num_traits::Float;
+
+pub struct SimulationBuilder<'a, T> {
+    range: Option<[T; 2]>,
+    dx: Option<T>,
+    dt: Option<T>,
+    cfl: Option<T>,
+    boundary: [BoundaryKind<T>; 2],
+    equation: Option<Box<dyn Equation<T> + Send + Sync>>,
+    init: Option<Box<dyn Fn(T) -> T + 'a>>,
+}
+
+impl<'a, T> Default for SimulationBuilder<'a, T> {
+    fn default() -> Self {
+        Self {
+            range: None,
+            dx: None,
+            dt: None,
+            cfl: None,
+            boundary: [BoundaryKind::Periodic, BoundaryKind::Periodic],
+            equation: None,
+            init: None,
+        }
+    }
+}
+
+impl<'a, T> SimulationBuilder<'a, T>
+where
+    T: Float,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Domain $[a, b)$, gridded as by `Simluation::new`
+    pub fn range(mut self, range: [T; 2]) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    pub fn dx(mut self, dx: T) -> Self {
+        self.dx = Some(dx);
+        self
+    }
+
+    /// Fixed time step, instead of `cfl`
+    pub fn dt(mut self, dt: T) -> Self {
+        self.dt = Some(dt);
+        self
+    }
+
+    /// Derive the time step from a CFL number by `Simluation::stable_dt` of
+    /// the initial state, assuming unit speed without an `equation`
+    pub fn cfl(mut self, cfl: T) -> Self {
+        self.cfl = Some(cfl);
+        self
+    }
+
+    /// Periodic if not set
+    pub fn boundary(mut self, boundary: Boundary<T>) -> Self {
+        self.boundary = boundary.sides();
+        self
+    }
+
+    pub fn boundaries(mut self, left: BoundaryKind<T>, right: BoundaryKind<T>) -> Self {
+        self.boundary = [left, right];
+        self
+    }
+
+    /// Equation to bind, see `Simluation::bind_equation`
+    pub fn equation(mut self, eq: Box<dyn Equation<T> + Send + Sync>) -> Self {
+        self.equation = Some(eq);
+        self
+    }
+
+    /// Initial state, zero if not set
+    pub fn init<F>(mut self, init: F) -> Self
+    where
+        F: Fn(T) -> T + 'a,
+    {
+        self.init = Some(Box::new(init));
+        self
+    }
+
+    pub fn build(self) -> Result<Simluation<T>, BuildError> {
+        let to_f64 = |x: T| x.to_f64().unwrap();
+        let range = self.range.ok_or(BuildError::MissingRange)?;
+        if range[0] >= range[1] || range[0].is_nan() || range[1].is_nan() {
+            return Err(BuildError::EmptyRange([to_f64(range[0]), to_f64(range[1])]));
+        }
+        let dx = self.dx.ok_or(BuildError::MissingDx)?;
+        if dx <= T::from(0).unwrap() || dx.is_nan() {
+            return Err(BuildError::NonPositiveDx(to_f64(dx)));
+        }
+
+        let zero = T::from(0).unwrap();
+        let init = self.init.unwrap_or_else(|| Box::new(move |_| zero));
+        let mut sim = match (self.dt, self.cfl) {
+            (Some(dt), None) => {
+                if dt <= zero || dt.is_nan() {
+                    return Err(BuildError::NonPositiveDt(to_f64(dt)));
+                }
+                Simluation::new(dx, dt, range, init)
+            }
+            (None, Some(cfl)) => {
+                if cfl <= zero || cfl.is_nan() {
+                    return Err(BuildError::NonPositiveCfl(to_f64(cfl)));
+                }
+                let mut sim = Simluation::new(dx, cfl * dx, range, init);
+                if let Some(eq) = self.equation.as_deref() {
+                    sim.dt = sim.stable_dt(eq, cfl);
+                }
+                sim
+            }
+            (None, None) => return Err(BuildError::MissingTimeStep),
+            (Some(_), Some(_)) => return Err(BuildError::ConflictingTimeStep),
+        };
+        sim.set_boundaries(self.boundary[0], self.boundary[1]);
+        if let Some(eq) = self.equation {
+            sim.bind_equation(eq);
+        }
+        Ok(sim)
+    }
+}
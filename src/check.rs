@@ -0,0 +1,90 @@
+//! # Sanity Checks
+//!
+//! The consistency checks, e.g. on the length of a new state, panic by
+//! default. Those guaranteed by the stencil indexing alone, e.g. on the
+//! lengths of the flux arrays, only run in debug builds. A thread can switch
+//! them to warnings instead, which are collected until `take_warnings`
+//! without printing anything, e.g. when embedding the solver in a server
+//! that must not abort on edge-case inputs and logs on its own terms.
+//!
+//! ```
+//! use fdm::check::{self, CheckMode};
+//!
+//! check::set_check_mode(CheckMode::Warn);
+//! // ... run the simulation ...
+//! for warning in check::take_warnings() {
+//!     println!("{}", warning);
+//! }
+//! ```
+
+use std::cell::{Cell, RefCell};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CheckMode {
+    /// Panic on a failed check
+    Panic,
+    /// Record a warning and carry on
+    Warn,
+}
+
+thread_local! {
+    static MODE: Cell<CheckMode> = const { Cell::new(CheckMode::Panic) };
+    static WARNINGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Set how failed checks are handled on the current thread
+pub fn set_check_mode(mode: CheckMode) {
+    MODE.with(|m| m.set(mode));
+}
+
+pub fn check_mode() -> CheckMode {
+    MODE.with(|m| m.get())
+}
+
+/// Drain the warnings recorded on the current thread
+pub fn take_warnings() -> Vec<String> {
+    WARNINGS.with(|w| w.replace(vec![]))
+}
+
+#[doc(hidden)]
+pub fn fail(message: String) {
+    match check_mode() {
+        CheckMode::Panic => panic!("{}", message),
+        CheckMode::Warn => WARNINGS.with(|w| w.borrow_mut().push(message)),
+    }
+}
+
+/// Like `assert_eq!`, but honoring the `CheckMode`
+///
+/// Evaluates to whether the check held.
+macro_rules! sanity_check_eq {
+    ($left:expr, $right:expr) => {{
+        let (left, right) = (&$left, &$right);
+        let held = left == right;
+        if !held {
+            $crate::check::fail(format!(
+                "sanity check failed at {}:{}: `{}` = {:?}, `{}` = {:?}",
+                file!(),
+                line!(),
+                stringify!($left),
+                left,
+                stringify!($right),
+                right
+            ));
+        }
+        held
+    }};
+}
+
+/// `sanity_check_eq!` compiled out of release builds, for the invariants of
+/// the stencil indexing checked on every step
+macro_rules! debug_sanity_check_eq {
+    ($left:expr, $right:expr) => {
+        if cfg!(debug_assertions) {
+            $crate::check::sanity_check_eq!($left, $right);
+        }
+    };
+}
+
+pub(crate) use debug_sanity_check_eq;
+pub(crate) use sanity_check_eq;
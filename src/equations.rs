@@ -1,6 +1,11 @@
-use crate::base::Equation;
+use crate::base::{ConservationLaw, Convexity, Equation, Simluation};
+use crate::numeric::Numeric;
+use crate::splitting::SourceIntegrator;
+use ndarray::{s, Array1};
 use num_traits::Float;
-use std::fmt::Debug;
+use std::cell::OnceCell;
+use std::fmt::{self, Debug};
+use std::ops::Range;
 
 #[derive(Debug, Copy, Clone)]
 pub struct Advection<T> {
@@ -9,7 +14,7 @@ pub struct Advection<T> {
 
 impl<T> Equation<T> for Advection<T>
 where
-    T: Float + Debug,
+    T: Numeric + Debug,
 {
     fn f(&self, u: T) -> T {
         u * self.a
@@ -20,6 +25,24 @@ where
     }
 }
 
+/// With the square entropy $\eta = \frac{1}{2} u^2$ and $q = \frac{1}{2} a u^2$
+impl<T> ConservationLaw<T> for Advection<T>
+where
+    T: Numeric + Debug,
+{
+    fn convexity(&self) -> Convexity {
+        Convexity::Linear
+    }
+
+    fn entropy(&self, u: T) -> Option<T> {
+        Some(u * u / T::from(2).unwrap())
+    }
+
+    fn entropy_flux(&self, u: T) -> Option<T> {
+        Some(self.a * u * u / T::from(2).unwrap())
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct InviscidBurger;
 
@@ -35,3 +58,341 @@ where
         u
     }
 }
+
+/// With the square entropy $\eta = \frac{1}{2} u^2$ and $q = \frac{1}{3} u^3$
+impl<T> ConservationLaw<T> for InviscidBurger
+where
+    T: Float,
+{
+    fn convexity(&self) -> Convexity {
+        Convexity::Convex
+    }
+
+    fn entropy(&self, u: T) -> Option<T> {
+        Some(u.powi(2) / T::from(2).unwrap())
+    }
+
+    fn entropy_flux(&self, u: T) -> Option<T> {
+        Some(u.powi(3) / T::from(3).unwrap())
+    }
+}
+
+/// # Viscous Burgers
+///
+/// $$
+/// u_t + \left(\frac{1}{2} u^2\right)_x = \nu u_{xx}
+/// $$
+///
+/// The viscosity smears the shocks of `InviscidBurger` into traveling waves
+/// of width $\sim \nu / (u_L - u_R)$. Keep $\nu \Delta t / \Delta x^2 \le \frac{1}{2}$.
+#[derive(Debug, Copy, Clone)]
+pub struct ViscousBurger<T> {
+    pub nu: T,
+}
+
+impl<T> Equation<T> for ViscousBurger<T>
+where
+    T: Float + Debug,
+{
+    fn f(&self, u: T) -> T {
+        u.powi(2) / T::from(2).unwrap()
+    }
+
+    fn df(&self, u: T) -> T {
+        u
+    }
+
+    fn viscosity(&self) -> Option<T> {
+        Some(self.nu)
+    }
+}
+
+/// The convexity and entropy pair of the inviscid flux, which the viscosity
+/// only dissipates
+impl<T> ConservationLaw<T> for ViscousBurger<T>
+where
+    T: Float + Debug,
+{
+    fn convexity(&self) -> Convexity {
+        Convexity::Convex
+    }
+
+    fn entropy(&self, u: T) -> Option<T> {
+        InviscidBurger.entropy(u)
+    }
+
+    fn entropy_flux(&self, u: T) -> Option<T> {
+        InviscidBurger.entropy_flux(u)
+    }
+}
+
+/// # Traffic Flow
+///
+/// The Lighthill-Whitham-Richards model for the density $\rho$
+///
+/// $$
+/// f(\rho) = v_{max} \rho \left(1 - \frac{\rho}{\rho_{max}}\right)
+/// $$
+#[derive(Debug, Copy, Clone)]
+pub struct Traffic<T> {
+    pub v_max: T,
+    pub rho_max: T,
+}
+
+impl<T> Equation<T> for Traffic<T>
+where
+    T: Float + Debug,
+{
+    fn f(&self, u: T) -> T {
+        self.v_max * u * (T::from(1).unwrap() - u / self.rho_max)
+    }
+
+    fn df(&self, u: T) -> T {
+        self.v_max * (T::from(1).unwrap() - T::from(2).unwrap() * u / self.rho_max)
+    }
+}
+
+impl<T> ConservationLaw<T> for Traffic<T>
+where
+    T: Float + Debug,
+{
+    fn convexity(&self) -> Convexity {
+        Convexity::Concave
+    }
+}
+
+/// # Buckley-Leverett
+///
+/// Two-phase flow in a porous medium, with the water saturation $u$ and the
+/// mobility ratio $M$ of oil to water
+///
+/// $$
+/// f(u) = \frac{u^2}{u^2 + M (1 - u)^2}
+/// $$
+///
+/// The flux is S-shaped on $[0, 1]$, so the Riemann solution is a shock
+/// attached to a rarefaction.
+#[derive(Debug, Copy, Clone)]
+pub struct BuckleyLeverett<T> {
+    pub mobility_ratio: T,
+}
+
+impl<T> Equation<T> for BuckleyLeverett<T>
+where
+    T: Float + Debug,
+{
+    fn f(&self, u: T) -> T {
+        let v = T::from(1).unwrap() - u;
+        u * u / (u * u + self.mobility_ratio * v * v)
+    }
+
+    fn df(&self, u: T) -> T {
+        let v = T::from(1).unwrap() - u;
+        let d = u * u + self.mobility_ratio * v * v;
+        T::from(2).unwrap() * self.mobility_ratio * u * v / (d * d)
+    }
+}
+
+impl<T> ConservationLaw<T> for BuckleyLeverett<T>
+where
+    T: Float + Debug,
+{
+    fn convexity(&self) -> Convexity {
+        Convexity::NonConvex
+    }
+}
+
+/// # Relaxed Traffic Flow
+///
+/// LWR traffic with a source relaxing the density towards an equilibrium
+/// profile $\rho_{eq}(x)$ at rate $1 / \tau$
+///
+/// $$
+/// \rho_t + f(\rho)_x = \frac{\rho_{eq}(x) - \rho}{\tau}
+/// $$
+///
+/// As an `Equation`, `Scheme::run` adds the source by a forward-Euler step,
+/// which is only stable for $\Delta t \le 2 \tau$. As a `SourceIntegrator` the
+/// source is integrated exactly, so it stays stable for a stiff
+/// $\tau \to 0$. Advance it with `Simluation::coupled_step`, which takes the
+/// exact integrator instead of the forward-Euler source.
+#[derive(Clone)]
+pub struct RelaxedTraffic<T, F> {
+    pub traffic: Traffic<T>,
+    pub tau: T,
+    pub equilibrium: F,
+}
+
+impl<T: Debug, F> Debug for RelaxedTraffic<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RelaxedTraffic")
+            .field("traffic", &self.traffic)
+            .field("tau", &self.tau)
+            .finish()
+    }
+}
+
+impl<T, F> Equation<T> for RelaxedTraffic<T, F>
+where
+    T: Float + Debug,
+    F: Fn(T) -> T + Clone,
+{
+    fn f(&self, u: T) -> T {
+        self.traffic.f(u)
+    }
+
+    fn df(&self, u: T) -> T {
+        self.traffic.df(u)
+    }
+
+    fn source(&self, u: T, x: T, _t: T) -> T {
+        ((self.equilibrium)(x) - u) / self.tau
+    }
+}
+
+/// The flux is that of `Traffic`, the relaxation only adds a source
+impl<T, F> ConservationLaw<T> for RelaxedTraffic<T, F>
+where
+    T: Float + Debug,
+    F: Fn(T) -> T + Clone,
+{
+    fn convexity(&self) -> Convexity {
+        self.traffic.convexity()
+    }
+}
+
+impl<T, F> SourceIntegrator<T> for RelaxedTraffic<T, F>
+where
+    T: Float,
+    F: Fn(T) -> T,
+{
+    fn integrate(&self, u: T, x: T, dt: T) -> T {
+        let u_eq = (self.equilibrium)(x);
+        u_eq + (u - u_eq) * (-dt / self.tau).exp()
+    }
+}
+
+/// # Warm Cache
+///
+/// Wraps an equation with $f$ precomputed once per step over the
+/// ghost-extended state, so the repeated `get_f` calls of a scheme (e.g. in
+/// both `speed` and `flux`) are served from slices instead of evaluating an
+/// expensive flux again. $f^{'}$ is computed on the first `df_array` call.
+///
+/// A single `Scheme::run` already evaluates $f$ once per cell, see
+/// `Extended`; warming pays off when several schemes run on the same state,
+/// e.g. to compare them.
+///
+/// ```
+/// use fdm::base::Simluation;
+/// use fdm::equations::{InviscidBurger, WarmCache};
+/// use fdm::schemes::{Scheme, Upwind};
+///
+/// let mut sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| x);
+/// let mut eq = WarmCache::new(&InviscidBurger);
+/// for _ in 0..10 {
+///     eq.warm(&sim, 1);
+///     sim.set_state(Upwind.run(&sim, &eq).unwrap());
+/// }
+/// ```
+///
+/// Arrays not matching the warmed state, e.g. after the state was changed
+/// without calling `warm` again, fall back to the wrapped equation.
+#[derive(Clone)]
+pub struct WarmCache<'a, T> {
+    eq: &'a dyn Equation<T>,
+    ext: usize,
+    u: Array1<T>,
+    f: Array1<T>,
+    df: OnceCell<Array1<T>>,
+}
+
+impl<T> Debug for WarmCache<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WarmCache")
+            .field("eq", &self.eq)
+            .field("ext", &self.ext)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T> WarmCache<'a, T>
+where
+    T: Numeric,
+{
+    pub fn new(eq: &'a dyn Equation<T>) -> Self {
+        Self {
+            eq,
+            ext: 0,
+            u: Array1::from(vec![]),
+            f: Array1::from(vec![]),
+            df: OnceCell::new(),
+        }
+    }
+
+    /// Precompute $f$ over the state extended by `ext` ghost cells, which
+    /// should be the widest extension the scheme requests
+    pub fn warm(&mut self, sim: &Simluation<T>, ext: usize) {
+        self.ext = ext;
+        self.u = sim.get_u(ext);
+        self.f = self.eq.f_array(&self.u);
+        self.df = OnceCell::new();
+    }
+
+    /// Range of the warmed state equal to `u`, if it is a centered part of it
+    fn lookup(&self, u: &Array1<T>) -> Option<Range<usize>> {
+        let n = self.u.len().checked_sub(2 * self.ext)?;
+        let ext = u.len().checked_sub(n)? / 2;
+        if u.len() != n + 2 * ext || ext > self.ext {
+            return None;
+        }
+        let range = self.ext - ext..self.ext + n + ext;
+        if self.u.slice(s![range.clone()]) == *u {
+            Some(range)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Equation<T> for WarmCache<'_, T>
+where
+    T: Numeric,
+{
+    fn f(&self, u: T) -> T {
+        self.eq.f(u)
+    }
+
+    fn df(&self, u: T) -> T {
+        self.eq.df(u)
+    }
+
+    fn f_array(&self, u: &Array1<T>) -> Array1<T> {
+        match self.lookup(u) {
+            Some(range) => self.f.slice(s![range]).to_owned(),
+            None => self.eq.f_array(u),
+        }
+    }
+
+    fn df_array(&self, u: &Array1<T>) -> Array1<T> {
+        match self.lookup(u) {
+            Some(range) => {
+                let df = self.df.get_or_init(|| self.eq.df_array(&self.u));
+                df.slice(s![range]).to_owned()
+            }
+            None => self.eq.df_array(u),
+        }
+    }
+
+    fn dispersion(&self) -> Option<T> {
+        self.eq.dispersion()
+    }
+
+    fn viscosity(&self) -> Option<T> {
+        self.eq.viscosity()
+    }
+
+    fn source(&self, u: T, x: T, t: T) -> T {
+        self.eq.source(u, x, t)
+    }
+}
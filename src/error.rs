@@ -0,0 +1,150 @@
+//! # Errors
+//!
+//! Problems found in a simulation setup, see `Simluation::validate`, and
+//! failures of a scheme while stepping or of a `SimulationBuilder`.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FdError {
+    /// The grid spacing is not positive
+    NonPositiveDx(f64),
+    /// The time step is not positive
+    NonPositiveDt(f64),
+    /// The largest $|f^{'}(u)| \Delta t / \Delta x$ of the state exceeds one
+    CflViolated(f64),
+    /// The state and the grid differ in length
+    LengthMismatch { grid: usize, state: usize },
+    /// The scheme only supports linear flux, but $f^{'}$ is not constant
+    NonlinearFlux,
+    /// A Dirichlet boundary value is not finite
+    InvalidBoundary(f64),
+    /// A ghost fill needs more interior points than the grid has
+    GridTooShort { needed: usize, len: usize },
+}
+
+impl fmt::Display for FdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FdError::NonPositiveDx(dx) => write!(f, "dx = {} is not positive", dx),
+            FdError::NonPositiveDt(dt) => write!(f, "dt = {} is not positive", dt),
+            FdError::CflViolated(cfl) => write!(f, "CFL number {} exceeds 1", cfl),
+            FdError::LengthMismatch { grid, state } => write!(
+                f,
+                "state of length {} does not match grid of length {}",
+                state, grid
+            ),
+            FdError::NonlinearFlux => write!(f, "scheme requires a linear flux"),
+            FdError::InvalidBoundary(b) => write!(f, "boundary value {} is not finite", b),
+            FdError::GridTooShort { needed, len } => write!(
+                f,
+                "ghost fill needs {} interior points, but the grid has {}",
+                needed, len
+            ),
+        }
+    }
+}
+
+impl Error for FdError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemeError {
+    /// The local CFL number $v$ of the cell at `location` exceeds one in
+    /// magnitude, with ghost cells reported at the nearest boundary cell
+    CflViolation { v: f64, location: usize },
+    /// A shallow water cell ran dry, see `DryCellError`
+    DryCell(DryCellError),
+    /// The CFL number of `StepMode::Adaptive` is not positive
+    NonPositiveCfl(f64),
+    /// The time step `dt` does not advance the time `t`, being not positive
+    /// or below the rounding of `t`
+    StalledTime { t: f64, dt: f64 },
+    /// The new state of a step differs in length from the grid, with the
+    /// sanity check not set to panic
+    LengthMismatch { grid: usize, state: usize },
+}
+
+impl fmt::Display for SchemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemeError::CflViolation { v, location } => {
+                write!(f, "Check the CFL condition! v = {} at cell {}", v, location)
+            }
+            SchemeError::DryCell(e) => e.fmt(f),
+            SchemeError::NonPositiveCfl(cfl) => write!(f, "CFL number {} is not positive", cfl),
+            SchemeError::StalledTime { t, dt } => {
+                write!(f, "dt = {} does not advance the time t = {}", dt, t)
+            }
+            SchemeError::LengthMismatch { grid, state } => write!(
+                f,
+                "new state of length {} does not match grid of length {}",
+                state, grid
+            ),
+        }
+    }
+}
+
+impl Error for SchemeError {}
+
+impl From<DryCellError> for SchemeError {
+    fn from(e: DryCellError) -> Self {
+        SchemeError::DryCell(e)
+    }
+}
+
+/// The water height `h` of the cell at `location` is not positive
+///
+/// Neither the velocity $hu / h$ nor the gravity wave speed $\sqrt{g h}$ is
+/// defined there, so the shallow water equations cannot step past it. Ghost
+/// cells are reported at the nearest boundary cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryCellError {
+    pub h: f64,
+    pub location: usize,
+}
+
+impl fmt::Display for DryCellError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cell {} is dry: h = {}", self.location, self.h)
+    }
+}
+
+impl Error for DryCellError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    /// No range was given to `SimulationBuilder`
+    MissingRange,
+    /// The range `[a, b]` does not satisfy $a < b$
+    EmptyRange([f64; 2]),
+    /// No grid spacing was given to `SimulationBuilder`
+    MissingDx,
+    /// The grid spacing is not positive
+    NonPositiveDx(f64),
+    /// The time step is not positive
+    NonPositiveDt(f64),
+    /// The CFL number is not positive
+    NonPositiveCfl(f64),
+    /// Neither `dt` nor `cfl` was given
+    MissingTimeStep,
+    /// Both `dt` and `cfl` were given
+    ConflictingTimeStep,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::MissingRange => write!(f, "range is not set"),
+            BuildError::EmptyRange(r) => write!(f, "range {:?} is empty", r),
+            BuildError::MissingDx => write!(f, "dx is not set"),
+            BuildError::NonPositiveDx(dx) => write!(f, "dx = {} is not positive", dx),
+            BuildError::NonPositiveDt(dt) => write!(f, "dt = {} is not positive", dt),
+            BuildError::NonPositiveCfl(cfl) => write!(f, "CFL number {} is not positive", cfl),
+            BuildError::MissingTimeStep => write!(f, "set either dt or cfl"),
+            BuildError::ConflictingTimeStep => write!(f, "dt and cfl are both set"),
+        }
+    }
+}
+
+impl Error for BuildError {}
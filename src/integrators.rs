@@ -0,0 +1,178 @@
+//! # Time Integrators
+//!
+//! A scheme defines the spatial discretization through its numerical flux,
+//! giving the forward-Euler increment
+//!
+//! $$
+//! R(u) = -\frac{\Delta t}{\Delta x} (h_{j+} - h_{j-})
+//! $$
+//!
+//! of `Scheme::residual`. A [`TimeIntegrator`] combines one or more of these
+//! stages into a step, so any flux scheme can be paired with any integrator
+//! via `Scheme::run_with`.
+
+use crate::base::Simluation;
+use crate::check::sanity_check_eq;
+use crate::error::SchemeError;
+use crate::numeric::Numeric;
+use ndarray::{Array1, Zip};
+use std::fmt::Debug;
+
+/// Stage increment $R(u)$ of the state held by a simulation
+pub type Residual<'a, T> = dyn Fn(&Simluation<T>) -> Result<Array1<T>, SchemeError> + 'a;
+
+pub trait TimeIntegrator<T>: Debug
+where
+    T: Numeric,
+{
+    /// The state after one time step, given the stage increment $R$, e.g.
+    /// `Scheme::residual`
+    fn advance(
+        &self,
+        sim: &Simluation<T>,
+        residual: &Residual<T>,
+    ) -> Result<Array1<T>, SchemeError>;
+
+    /// Like `advance`, but writing the state into `out`, e.g. the buffer of
+    /// the previous step
+    ///
+    /// Defaults to moving the result of `advance` into `out`.
+    fn advance_into(
+        &self,
+        sim: &Simluation<T>,
+        residual: &Residual<T>,
+        out: &mut Array1<T>,
+    ) -> Result<(), SchemeError> {
+        *out = self.advance(sim, residual)?;
+        Ok(())
+    }
+}
+
+/// # Integrator: Forward Euler
+///
+/// $$
+/// u^{n+1} = u^{n} + R(u^{n})
+/// $$
+///
+/// which is what `Scheme::run` uses.
+#[derive(Debug, Copy, Clone)]
+pub struct ForwardEuler;
+
+impl<T> TimeIntegrator<T> for ForwardEuler
+where
+    T: Numeric,
+{
+    fn advance(
+        &self,
+        sim: &Simluation<T>,
+        residual: &Residual<T>,
+    ) -> Result<Array1<T>, SchemeError> {
+        let mut state = Array1::from(vec![]);
+        self.advance_into(sim, residual, &mut state)?;
+        Ok(state)
+    }
+
+    /// Reallocates `out` only if its length differs from the grid
+    fn advance_into(
+        &self,
+        sim: &Simluation<T>,
+        residual: &Residual<T>,
+        out: &mut Array1<T>,
+    ) -> Result<(), SchemeError> {
+        let residual = residual(sim)?;
+        if !sanity_check_eq!(sim.len(), residual.len()) {
+            *out = sim.state.clone();
+            return Ok(());
+        }
+        if out.len() != sim.len() {
+            *out = Array1::from_elem(sim.len(), T::from(0).unwrap());
+        }
+        Zip::from(out)
+            .and(&sim.state)
+            .and(&residual)
+            .apply(|out, &u, &r| *out = u + r);
+        Ok(())
+    }
+}
+
+/// # Integrator: SSP-RK2
+///
+/// Heun's method in the Shu-Osher form
+///
+/// $$
+/// \begin{aligned}
+/// u^{(1)} &= u^{n} + R(u^{n}) \\\\
+/// u^{n+1} &= \frac{1}{2} u^{n} + \frac{1}{2} \left(u^{(1)} + R(u^{(1)})\right)
+/// \end{aligned}
+/// $$
+///
+/// the optimal two-stage method keeping the TVD property at the forward-Euler
+/// CFL limit.
+#[derive(Debug, Copy, Clone)]
+pub struct SspRk2;
+
+impl<T> TimeIntegrator<T> for SspRk2
+where
+    T: Numeric,
+{
+    fn advance(
+        &self,
+        sim: &Simluation<T>,
+        residual: &Residual<T>,
+    ) -> Result<Array1<T>, SchemeError> {
+        let one = T::from(1).unwrap();
+        let half = one / T::from(2).unwrap();
+        let u0 = &sim.state;
+        let mut stage = sim.stage();
+
+        let u1 = stage.rk_substep(&residual(&stage)?, one);
+        stage.set_state(u1);
+        let u2 = stage.rk_substep(&residual(&stage)?, one);
+        Ok(Array1::from_shape_fn(sim.len(), |j| {
+            half * u0[j] + half * u2[j]
+        }))
+    }
+}
+
+/// # Integrator: SSP-RK3
+///
+/// The strong-stability-preserving Runge-Kutta method of Shu and Osher
+///
+/// $$
+/// \begin{aligned}
+/// u^{(1)} &= u^{n} + R(u^{n}) \\\\
+/// u^{(2)} &= \frac{3}{4} u^{n} + \frac{1}{4} \left(u^{(1)} + R(u^{(1)})\right) \\\\
+/// u^{n+1} &= \frac{1}{3} u^{n} + \frac{2}{3} \left(u^{(2)} + R(u^{(2)})\right)
+/// \end{aligned}
+/// $$
+///
+/// which keeps the TVD property of a forward-Euler step at the same CFL limit.
+#[derive(Debug, Copy, Clone)]
+pub struct SspRk3;
+
+impl<T> TimeIntegrator<T> for SspRk3
+where
+    T: Numeric,
+{
+    fn advance(
+        &self,
+        sim: &Simluation<T>,
+        residual: &Residual<T>,
+    ) -> Result<Array1<T>, SchemeError> {
+        let one = T::from(1).unwrap();
+        let (a, b) = (T::from(0.75).unwrap(), T::from(0.25).unwrap());
+        let (c, d) = (
+            one / T::from(3).unwrap(),
+            T::from(2).unwrap() / T::from(3).unwrap(),
+        );
+        let u0 = &sim.state;
+        let mut stage = sim.stage();
+
+        let u1 = stage.rk_substep(&residual(&stage)?, one);
+        stage.set_state(u1);
+        let u2 = stage.rk_substep(&residual(&stage)?, one);
+        stage.set_state(Array1::from_shape_fn(sim.len(), |j| a * u0[j] + b * u2[j]));
+        let u3 = stage.rk_substep(&residual(&stage)?, one);
+        Ok(Array1::from_shape_fn(sim.len(), |j| c * u0[j] + d * u3[j]))
+    }
+}
@@ -0,0 +1,183 @@
+//! # Input and Output
+//!
+//! CSV snapshots of the state, written as `x,u` lines after a `# t = ...`
+//! comment holding the simulation time, periodic checkpoints
+//! of long runs built on them, per-cell diagnostic tables, and raw gnuplot
+//! data blocks.
+
+use crate::base::{Equation, Simluation};
+use crate::schemes::Scheme;
+use num_traits::Float;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// One cell of `Simluation::table`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CellRow<T> {
+    pub x: T,
+    pub u: T,
+    pub f: T,
+    pub df: T,
+    /// Local CFL number, see `Simluation::cfl_map`
+    pub cfl: T,
+}
+
+/// Write the state every `interval` steps to `dir/{prefix}{step:06}.csv`
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    pub dir: PathBuf,
+    pub prefix: String,
+    pub interval: usize,
+}
+
+impl Checkpoint {
+    pub fn new<P: AsRef<Path>>(dir: P, interval: usize) -> Self {
+        assert!(interval > 0, "Checkpoint interval must be positive");
+        Self {
+            dir: dir.as_ref().into(),
+            prefix: "checkpoint_".into(),
+            interval,
+        }
+    }
+
+    /// Path of the checkpoint after `step` steps
+    pub fn path(&self, step: usize) -> PathBuf {
+        self.dir.join(format!("{}{:06}.csv", self.prefix, step))
+    }
+}
+
+impl<T> Simluation<T>
+where
+    T: Float,
+{
+    /// Write the grid and the state as `x,u` lines, preceded by the time
+    /// as a `# t = ...` comment
+    pub fn save_csv<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(fs::File::create(path)?);
+        writeln!(writer, "# t = {:e}", self.t.to_f64().unwrap())?;
+        for (x, u) in self.grid.iter().zip(self.state.iter()) {
+            writeln!(
+                writer,
+                "{:e},{:e}",
+                x.to_f64().unwrap(),
+                u.to_f64().unwrap()
+            )?;
+        }
+        writer.flush()
+    }
+
+    /// Write the grid and the state as a gnuplot data block, i.e. `x u` lines
+    /// followed by a blank line separating it from the next block
+    ///
+    /// Successive snapshots appended to one file can be picked with `index`
+    /// in a hand-written gnuplot script.
+    pub fn to_gnuplot_data<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for (x, u) in self.grid.iter().zip(self.state.iter()) {
+            writeln!(
+                writer,
+                "{:e} {:e}",
+                x.to_f64().unwrap(),
+                u.to_f64().unwrap()
+            )?;
+        }
+        writeln!(writer)
+    }
+
+    /// Restore the state from a file written by `save_csv` on the same grid
+    ///
+    /// The time is restored as well, so a resumed run continues its clock.
+    /// A file without the `# t = ...` comment leaves the time unchanged.
+    pub fn load_csv<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let text = fs::read_to_string(path)?;
+        let parse = |line: &str, value: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .map(|v| T::from(v).unwrap())
+                .map_err(|e| invalid(format!("{:?}: {}", line, e)))
+        };
+        let mut t = None;
+        let mut lines = text.lines().peekable();
+        while let Some(comment) = lines.next_if(|line| line.starts_with('#')) {
+            if let Some(value) = comment.trim_start_matches('#').trim().strip_prefix("t =") {
+                t = Some(parse(comment, value)?);
+            }
+        }
+        let state = lines
+            .map(|line| {
+                let u = line.rsplit(',').next().unwrap_or("");
+                parse(line, u)
+            })
+            .collect::<io::Result<Vec<T>>>()?;
+        if state.len() != self.len() {
+            return Err(invalid(format!(
+                "checkpoint of length {} does not match grid of length {}",
+                state.len(),
+                self.len()
+            )));
+        }
+        self.set_state(state.into());
+        if let Some(t) = t {
+            self.t = t;
+        }
+        Ok(())
+    }
+
+    /// Per-cell snapshot of the state together with $f$, $f^{'}$ and the
+    /// local CFL number, one row per cell
+    pub fn table(&self, eq: &dyn Equation<T>) -> Vec<CellRow<T>> {
+        let f = self.get_f(eq, 0);
+        let df = eq.df_array(&self.state);
+        let cfl = self.cfl_map(eq);
+        (0..self.len())
+            .map(|j| CellRow {
+                x: self.grid[j],
+                u: self.state[j],
+                f: f[j],
+                df: df[j],
+                cfl: cfl[j],
+            })
+            .collect()
+    }
+
+    /// Write `table` as CSV with a `x,u,f,df,cfl` header
+    pub fn save_table_csv<P: AsRef<Path>>(&self, path: P, eq: &dyn Equation<T>) -> io::Result<()> {
+        let mut writer = BufWriter::new(fs::File::create(path)?);
+        writeln!(writer, "x,u,f,df,cfl")?;
+        for row in self.table(eq) {
+            let values: Vec<String> = [row.x, row.u, row.f, row.df, row.cfl]
+                .iter()
+                .map(|v| format!("{:e}", v.to_f64().unwrap()))
+                .collect();
+            writeln!(writer, "{}", values.join(","))?;
+        }
+        writer.flush()
+    }
+
+    /// Advance `steps` time steps by `step`, writing a checkpoint every
+    /// `checkpoint.interval` steps
+    ///
+    /// Returns the paths written, in order. A failing step, e.g. on a CFL
+    /// violation, is returned as an `io::Error` of kind `Other`.
+    pub fn run_with_checkpoints(
+        &mut self,
+        scheme: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+        steps: usize,
+        checkpoint: &Checkpoint,
+    ) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(&checkpoint.dir)?;
+        let mut written = vec![];
+        for step in 1..=steps {
+            self.step(scheme, eq).map_err(io::Error::other)?;
+            if step % checkpoint.interval == 0 {
+                let path = checkpoint.path(step);
+                self.save_csv(&path)?;
+                written.push(path);
+            }
+        }
+        Ok(written)
+    }
+}
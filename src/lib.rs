@@ -1,8 +1,28 @@
+#[cfg(feature = "gif")]
+pub mod animation;
 pub mod base;
+pub mod benchmark;
+pub mod boundary;
+pub mod builder;
+pub mod check;
 pub mod equations;
+pub mod error;
+pub mod integrators;
+pub mod io;
+pub mod limiters;
+pub mod numeric;
+pub mod parallel;
+pub mod plot;
+pub mod probes;
+pub mod riemann;
 pub mod schemes;
+pub mod spectral;
+pub mod splitting;
+pub mod sweep;
+pub mod systems;
 
 pub use base::Equation;
+pub use numeric::Numeric;
 pub use schemes::Scheme;
 
 pub type BoxedEquation = Box<dyn Equation<f64> + Send + Sync + 'static>;
@@ -0,0 +1,84 @@
+//! # Slope Limiters
+//!
+//! A limiter scales the forward difference of a cell by $\phi(r)$ of the
+//! ratio of consecutive slopes
+//!
+//! $$
+//! r_j = \frac{u_j - u_{j-1}}{u_{j+1} - u_j},
+//! $$
+//!
+//! giving the limited slope $\sigma_j = \phi(r_j) (u_{j+1} - u_j)$ of a
+//! piecewise-linear reconstruction such as `Muscl`.
+
+use num_traits::Float;
+use std::fmt::Debug;
+
+pub trait Limiter<T>: Debug {
+    fn phi(&self, r: T) -> T;
+}
+
+/// ## Limiter: Minmod
+///
+/// $$
+/// \phi(r) = \max(0, \min(1, r))
+/// $$
+
+#[derive(Debug, Copy, Clone)]
+pub struct Minmod;
+
+impl<T: Float> Limiter<T> for Minmod {
+    fn phi(&self, r: T) -> T {
+        T::from(0).unwrap().max(r.min(T::from(1).unwrap()))
+    }
+}
+
+/// ## Limiter: Superbee
+///
+/// $$
+/// \phi(r) = \max(0, \min(2r, 1), \min(r, 2))
+/// $$
+
+#[derive(Debug, Copy, Clone)]
+pub struct Superbee;
+
+impl<T: Float> Limiter<T> for Superbee {
+    fn phi(&self, r: T) -> T {
+        let one = T::from(1).unwrap();
+        let two = T::from(2).unwrap();
+        T::from(0).unwrap().max((two * r).min(one)).max(r.min(two))
+    }
+}
+
+/// ## Limiter: van Leer
+///
+/// $$
+/// \phi(r) = \frac{r + |r|}{1 + |r|}
+/// $$
+
+#[derive(Debug, Copy, Clone)]
+pub struct VanLeer;
+
+impl<T: Float> Limiter<T> for VanLeer {
+    fn phi(&self, r: T) -> T {
+        (r + r.abs()) / (T::from(1).unwrap() + r.abs())
+    }
+}
+
+/// ## Limiter: Monotonized Central
+///
+/// $$
+/// \phi(r) = \max\left(0, \min\left(2r, \frac{1 + r}{2}, 2\right)\right)
+/// $$
+
+#[derive(Debug, Copy, Clone)]
+pub struct MonotonizedCentral;
+
+impl<T: Float> Limiter<T> for MonotonizedCentral {
+    fn phi(&self, r: T) -> T {
+        let one = T::from(1).unwrap();
+        let two = T::from(2).unwrap();
+        T::from(0)
+            .unwrap()
+            .max((two * r).min((one + r) / two).min(two))
+    }
+}
@@ -1,6 +1,7 @@
 use clap::Clap;
 use fdm::base::{Equation, Simluation};
 use fdm::equations::{Advection, InviscidBurger};
+use fdm::plot::Animation;
 use fdm::schemes::{BeamWarming, LaxFriedrichs, LaxWendroff, Scheme, Upwind};
 use gnuplot::{AxesCommon, Figure, Fix, Font};
 use itertools::iproduct;
@@ -12,11 +13,33 @@ use std::fs;
 struct Args {
     #[clap(short, long, default_value = "outputs")]
     output_dir: String,
+    /// Delay between animation frames in 1/100 s
+    #[clap(long, default_value = "2")]
+    delay: u32,
+    #[clap(long, default_value = "480")]
+    width: u32,
+    #[clap(long, default_value = "360")]
+    height: u32,
+    /// Skip the gif optimization
+    #[clap(long)]
+    no_optimize: bool,
+}
+
+impl Args {
+    fn animation(&self) -> Animation {
+        Animation {
+            delay: self.delay,
+            width: self.width,
+            height: self.height,
+            optimize: !self.no_optimize,
+        }
+    }
 }
 
 fn main() {
     let args = Args::parse();
     fs::create_dir_all(&args.output_dir).unwrap();
+    let animation = args.animation();
 
     // equations
     let mut eqs: HashMap<String, Box<dyn Equation<f64>>> = HashMap::new();
@@ -41,7 +64,7 @@ fn main() {
     inits.insert("Sine".into(), Box::new(|x: f64| (PI * x).sin()));
     inits.insert(
         "Square".into(),
-        Box::new(|x: f64| if x >= 0. && x <= 1. { 1.0 } else { 0. }),
+        Box::new(|x: f64| if (0. ..=1.).contains(&x) { 1.0 } else { 0. }),
     );
 
     for ((eq_name, eq), (init_name, init), (scheme_name, scheme)) in
@@ -50,10 +73,8 @@ fn main() {
         let mut fig = Figure::new();
         let name = format!("{}-{}-{}", eq_name, init_name, scheme_name);
         println!("Processing {}", name);
-        fig.set_title(&name).set_terminal(
-            "gif animate optimize delay 2 size 480,360",
-            &format!("{}/{}.gif", args.output_dir, name),
-        );
+        fig.set_title(&name);
+        animation.apply(&mut fig, &format!("{}/{}.gif", args.output_dir, name));
 
         let mut sim = Simluation::<f64>::new(dx, dt, boundary, init);
 
@@ -69,7 +90,7 @@ fn main() {
                 .set_y_range(Fix(-1.5), Fix(1.5))
                 .set_x_range(Fix(boundary[0]), Fix(boundary[1]));
 
-            sim.set_state(scheme.run(&sim, &**eq));
+            sim.step(&**scheme, &**eq).unwrap();
             ax.lines(&sim.grid, &sim.state, &[]);
         }
 
@@ -0,0 +1,99 @@
+//! # Numeric
+//!
+//! The schemes only rely on a handful of numeric operations: the four
+//! arithmetic operators, negation, comparison, `abs`, and conversion from a
+//! few constants (`T::from(2).unwrap()`) via [`NumCast`]. [`Numeric`]
+//! collects exactly these, so the core of the solver can run on types which
+//! are not a full [`Float`], e.g. fixed-point or interval arithmetic.
+//!
+//! Every [`Float`] (in particular `f32` and `f64`) is [`Numeric`] already.
+//! For a custom type, implement the operators, [`PartialOrd`],
+//! [`NumCast`] together with its supertrait [`ToPrimitive`](num_traits::ToPrimitive),
+//! and finally `abs`:
+//!
+//! ```
+//! use fdm::numeric::Numeric;
+//! use num_traits::{NumCast, ToPrimitive};
+//! use std::ops::{Add, Div, Mul, Neg, Sub};
+//!
+//! #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+//! struct Fixed(i64);
+//!
+//! const SCALE: f64 = 1e6;
+//!
+//! impl Add for Fixed {
+//!     type Output = Self;
+//!     fn add(self, rhs: Self) -> Self { Fixed(self.0 + rhs.0) }
+//! }
+//! impl Sub for Fixed {
+//!     type Output = Self;
+//!     fn sub(self, rhs: Self) -> Self { Fixed(self.0 - rhs.0) }
+//! }
+//! impl Mul for Fixed {
+//!     type Output = Self;
+//!     fn mul(self, rhs: Self) -> Self { Fixed(self.0 * rhs.0 / SCALE as i64) }
+//! }
+//! impl Div for Fixed {
+//!     type Output = Self;
+//!     fn div(self, rhs: Self) -> Self { Fixed(self.0 * SCALE as i64 / rhs.0) }
+//! }
+//! impl Neg for Fixed {
+//!     type Output = Self;
+//!     fn neg(self) -> Self { Fixed(-self.0) }
+//! }
+//! impl ToPrimitive for Fixed {
+//!     fn to_i64(&self) -> Option<i64> { Some(self.0 / SCALE as i64) }
+//!     fn to_u64(&self) -> Option<u64> { self.to_i64()?.to_u64() }
+//!     fn to_f64(&self) -> Option<f64> { Some(self.0 as f64 / SCALE) }
+//! }
+//! impl NumCast for Fixed {
+//!     fn from<N: ToPrimitive>(n: N) -> Option<Self> {
+//!         Some(Fixed((n.to_f64()? * SCALE).round() as i64))
+//!     }
+//! }
+//! impl Numeric for Fixed {
+//!     fn abs(self) -> Self { Fixed(self.0.abs()) }
+//! }
+//! ```
+
+use ndarray::{Array1, CowArray, Ix1};
+use num_traits::{Float, NumCast};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+pub trait Numeric:
+    Copy
+    + PartialOrd
+    + NumCast
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn abs(self) -> Self;
+}
+
+impl<T: Float> Numeric for T {
+    fn abs(self) -> Self {
+        Float::abs(self)
+    }
+}
+
+/// Arrays viewed as `f64`, e.g. for gnuplot which only supports `f64`
+///
+/// For `f64` itself this borrows the array instead of converting it.
+pub trait AsF64: Copy {
+    fn as_f64_array(a: &Array1<Self>) -> CowArray<'_, f64, Ix1>;
+}
+
+impl AsF64 for f64 {
+    fn as_f64_array(a: &Array1<Self>) -> CowArray<'_, f64, Ix1> {
+        CowArray::from(a.view())
+    }
+}
+
+impl AsF64 for f32 {
+    fn as_f64_array(a: &Array1<Self>) -> CowArray<'_, f64, Ix1> {
+        CowArray::from(a.mapv(|x| x as f64))
+    }
+}
@@ -0,0 +1,53 @@
+//! # Parallel Loops
+//!
+//! With the `parallel` feature, the per-cell loops built on these helpers
+//! are split across the rayon thread pool once the grid is large enough to
+//! pay for it. Every entry is still computed by the same expression on its
+//! own, so the result is bit-identical to the serial loop.
+//!
+//! Without the feature, rayon is not a dependency and the loops are plain
+//! iterators.
+
+use ndarray::Array1;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Values a parallel loop hands between threads
+///
+/// This is `Send + Sync` with the `parallel` feature and holds for every
+/// type without it, so only the schemes with a parallel loop, e.g. `Upwind`
+/// and `LaxWendroff`, ask for thread-safe values, and only when the feature
+/// is enabled.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Send + Sync {}
+
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync> MaybeSync for T {}
+
+/// `MaybeSync` without the `parallel` feature, holding for every type
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSync for T {}
+
+/// Fewest entries handed to a single rayon task
+#[cfg(feature = "parallel")]
+const MIN_LEN: usize = 1 << 12;
+
+/// `[f(0), ..., f(n - 1)]`
+pub(crate) fn from_fn<T, F>(n: usize, f: F) -> Array1<T>
+where
+    T: MaybeSync,
+    F: Fn(usize) -> T + MaybeSync,
+{
+    #[cfg(feature = "parallel")]
+    let v: Vec<T> = (0..n)
+        .into_par_iter()
+        .with_min_len(MIN_LEN)
+        .map(f)
+        .collect();
+    #[cfg(not(feature = "parallel"))]
+    let v: Vec<T> = (0..n).map(f).collect();
+    Array1::from(v)
+}
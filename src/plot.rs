@@ -0,0 +1,58 @@
+//! # Plotting
+//!
+//! Animation settings for the gnuplot driver.
+
+use gnuplot::Figure;
+
+/// Anything accepting a gnuplot terminal, e.g. a `Figure`
+pub trait Plotter {
+    fn set_terminal(&mut self, terminal: &str, output_file: &str);
+}
+
+impl Plotter for Figure {
+    fn set_terminal(&mut self, terminal: &str, output_file: &str) {
+        Figure::set_terminal(self, terminal, output_file);
+    }
+}
+
+/// Parameters of an animated GIF terminal
+///
+/// The default matches the previously hardcoded
+/// `gif animate optimize delay 2 size 480,360`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Animation {
+    /// Delay between frames in 1/100 s
+    pub delay: u32,
+    pub width: u32,
+    pub height: u32,
+    pub optimize: bool,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self {
+            delay: 2,
+            width: 480,
+            height: 360,
+            optimize: true,
+        }
+    }
+}
+
+impl Animation {
+    /// The gnuplot terminal string
+    pub fn terminal(&self) -> String {
+        format!(
+            "gif animate {}delay {} size {},{}",
+            if self.optimize { "optimize " } else { "" },
+            self.delay,
+            self.width,
+            self.height
+        )
+    }
+
+    /// Direct the animation of `plotter` into `output_file`
+    pub fn apply(&self, plotter: &mut dyn Plotter, output_file: &str) {
+        plotter.set_terminal(&self.terminal(), output_file);
+    }
+}
@@ -0,0 +1,77 @@
+//! # Probes
+//!
+//! Virtual sensors recording the state at fixed coordinates over time.
+
+use crate::base::{Equation, Simluation};
+use crate::error::SchemeError;
+use crate::schemes::Scheme;
+use num_traits::Float;
+
+/// What a probe records while its coordinate lies outside the domain
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OutOfDomain {
+    /// Record `NaN`, keeping every series aligned with the steps
+    Nan,
+    /// Record nothing
+    Skip,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProbeRecorder<T> {
+    pub probes: Vec<T>,
+    pub out_of_domain: OutOfDomain,
+    series: Vec<Vec<T>>,
+}
+
+impl<T> ProbeRecorder<T>
+where
+    T: Float,
+{
+    pub fn new(probes: Vec<T>) -> Self {
+        let series = vec![vec![]; probes.len()];
+        Self {
+            probes,
+            out_of_domain: OutOfDomain::Nan,
+            series,
+        }
+    }
+
+    /// Append the interpolated state at every probe
+    pub fn record(&mut self, sim: &Simluation<T>) {
+        for (&x, series) in self.probes.iter().zip(self.series.iter_mut()) {
+            match (sim.sample_at(x), self.out_of_domain) {
+                (Some(u), _) => series.push(u),
+                (None, OutOfDomain::Nan) => series.push(T::nan()),
+                (None, OutOfDomain::Skip) => {}
+            }
+        }
+    }
+
+    /// Recorded time series, one per probe in the order of `probes`
+    pub fn series(&self) -> &[Vec<T>] {
+        &self.series
+    }
+}
+
+impl<T> Simluation<T>
+where
+    T: Float,
+{
+    /// Advance `steps` time steps by `step`, recording the probes after each
+    ///
+    /// Fails with the first error of `step`, e.g. a CFL violation, keeping
+    /// the samples recorded up to it.
+    pub fn probe_series(
+        &mut self,
+        scheme: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+        recorder: &mut ProbeRecorder<T>,
+        steps: usize,
+    ) -> Result<(), SchemeError> {
+        for _ in 0..steps {
+            self.step(scheme, eq)?;
+            recorder.record(self);
+        }
+        Ok(())
+    }
+}
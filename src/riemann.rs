@@ -0,0 +1,102 @@
+//! # Exact Riemann Solver
+//!
+//! Self-similar solution of a scalar conservation law with Riemann data
+//!
+//! $$
+//! u(x, 0) = \begin{cases}
+//! u_L,& x < 0 \\\\
+//! u_R,& x > 0
+//! \end{cases}
+//! $$
+//!
+//! which only depends on $\xi = x / t$. It serves as the analytic reference
+//! for validating schemes on Riemann problems.
+
+use crate::base::{Equation, Simluation};
+use crate::benchmark::ErrorNorms;
+use num_traits::Float;
+
+/// Value of the exact solution at $\xi = x / t$ for a convex flux $f$
+///
+/// If $u_L > u_R$ the solution is a shock travelling at the Rankine-Hugoniot
+/// speed
+///
+/// $$
+/// s = \frac{f(u_L) - f(u_R)}{u_L - u_R},
+/// $$
+///
+/// otherwise it is a rarefaction fan where $f^{'}(u) = \xi$ for
+/// $f^{'}(u_L) < \xi < f^{'}(u_R)$, which is inverted by bisection.
+pub fn riemann_exact<T>(eq: &dyn Equation<T>, u_left: T, u_right: T, xi: T) -> T
+where
+    T: Float,
+{
+    if u_left > u_right {
+        // shock
+        let s = (eq.f(u_left) - eq.f(u_right)) / (u_left - u_right);
+        if xi < s {
+            u_left
+        } else {
+            u_right
+        }
+    } else if xi <= eq.df(u_left) {
+        u_left
+    } else if xi >= eq.df(u_right) {
+        u_right
+    } else {
+        // inside the rarefaction fan, f' is increasing for a convex flux
+        characteristic_state(eq, u_left, u_right, xi)
+    }
+}
+
+/// State $u$ between `lo` and `hi` with $f^{'}(u) = \xi$, by bisection
+///
+/// Requires $f^{'}(lo) < \xi < f^{'}(hi)$ with $f^{'}$ monotone in between,
+/// while `lo` may be larger than `hi`. With $\xi = 0$ this is the sonic
+/// point of a transonic rarefaction.
+pub(crate) fn characteristic_state<T>(eq: &dyn Equation<T>, lo: T, hi: T, xi: T) -> T
+where
+    T: Float,
+{
+    let two = T::from(2).unwrap();
+    let (mut lo, mut hi) = (lo, hi);
+    loop {
+        let mid = (lo + hi) / two;
+        if mid == lo || mid == hi {
+            return mid;
+        }
+        if eq.df(mid) < xi {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+}
+
+impl<T> Simluation<T>
+where
+    T: Float,
+{
+    /// Error of the state collapsed onto $\xi = (x - x_0) / t$ against the
+    /// exact Riemann solution
+    ///
+    /// The norms are taken over $\xi$, i.e. weighted by $\Delta \xi = \Delta x / t$,
+    /// so runs of a scheme at different times are directly comparable. A
+    /// consistent scheme collapses onto the single self-similar curve, with the
+    /// error shrinking as $t$ grows and the smeared layers narrow in $\xi$.
+    pub fn self_similar_collapse(
+        &self,
+        eq: &dyn Equation<T>,
+        u_left: T,
+        u_right: T,
+        x0: T,
+        t: T,
+    ) -> ErrorNorms<T> {
+        let errors = self.error_norms(&|x| riemann_exact(eq, u_left, u_right, (x - x0) / t));
+        ErrorNorms {
+            l1: errors.l1 / t,
+            l2: errors.l2 / t.sqrt(),
+            linf: errors.linf,
+        }
+    }
+}
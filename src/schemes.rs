@@ -1,8 +1,19 @@
 use crate::base::Equation;
 use crate::base::Simluation;
+use crate::boundary::Boundary;
+use crate::check::debug_sanity_check_eq;
+use crate::equations::Advection;
+use crate::error::SchemeError;
+use crate::integrators::{ForwardEuler, TimeIntegrator};
+use crate::limiters::Limiter;
+use crate::numeric::Numeric;
+use crate::parallel::{self, MaybeSync};
+use crate::riemann::characteristic_state;
 use itertools::izip;
-use ndarray::Array1;
+use ndarray::{s, Array1, CowArray, Ix1, Zip};
+use num_complex::Complex;
 use num_traits::Float;
+use std::cell::Cell;
 use std::fmt::Debug;
 
 /// # Scheme
@@ -19,10 +30,9 @@ use std::fmt::Debug;
 /// $$
 ///
 /// where $h_{j+}^{n}, h_{j-}^{n}$ are the numerical flux.
-
-pub trait Scheme<T>: Debug
+pub trait Scheme<T>: Debug + CloneScheme<T>
 where
-    T: Float,
+    T: Numeric,
 {
     /// # Spreading speed
     ///
@@ -42,14 +52,15 @@ where
     /// \end{cases}
     /// $$
     ///
-    /// The return size = n + ext
-
-    fn speed(&self, sim: &Simluation<T>, eq: &dyn Equation<T>, ext: usize) -> [Array1<T>; 2] {
+    /// The return size = n + ext. Fails with `SchemeError::CflViolation` if
+    /// any $|v| > 1$.
+    fn speed(&self, extended: &Extended<T>, ext: usize) -> Result<[Array1<T>; 2], SchemeError> {
+        let (sim, eq) = (extended.sim, extended.eq);
         let n = sim.len();
         let dt_over_dx = sim.dt_over_dx();
 
         // extended u: [n+2*(ext+1)]
-        let u = sim.get_u(ext + 1);
+        let u = extended.u(ext + 1);
         let u_iter = u.iter();
 
         // compute difference of u: [n+2*ext+1]
@@ -59,7 +70,7 @@ where
             .map(|(&l, &r)| r - l);
 
         // extended f: [n+2*(ext+1)]
-        let f = sim.get_f(eq, ext + 1);
+        let f = extended.f(ext + 1);
         let f_iter = f.iter();
 
         // compute difference of f: [n+2*ext+1]
@@ -68,19 +79,22 @@ where
             .zip(f_iter.clone().skip(1))
             .map(|(&l, &r)| r - l);
 
-        // compute v each case
-        let compute_v = |(&u, du, df)| {
+        // compute v each case, where entry k belongs to cell k - ext
+        let compute_v = |(k, (&u, du, df)): (usize, (&T, T, T))| {
             let df_du = if du == T::from(0).unwrap() {
                 eq.df(u)
             } else {
                 df / du
             };
             let v = df_du * dt_over_dx;
-            assert!(
-                v.abs() <= T::from(1).unwrap(),
-                format!("Check the CRL condition! {:?}", v.to_f64().unwrap())
-            );
-            v
+            if v.abs() <= T::from(1).unwrap() {
+                Ok(v)
+            } else {
+                Err(SchemeError::CflViolation {
+                    v: v.to_f64().unwrap(),
+                    location: (k.max(ext) - ext).min(n - 1),
+                })
+            }
         };
 
         // v+: [n]
@@ -89,8 +103,9 @@ where
             du_iter.clone().skip(1), // u_{j+1} - u_j
             df_iter.clone().skip(1), // f_{j+1} - f_j
         )
+        .enumerate()
         .map(compute_v)
-        .collect();
+        .collect::<Result<_, _>>()?;
 
         // v-: [n]
         let v_neg: Vec<T> = izip!(
@@ -98,14 +113,15 @@ where
             du_iter.take(n + ext * 2), // u_{j} - u_{j-1}
             df_iter.take(n + ext * 2), // f_{j} - f_{j-1}
         )
+        .enumerate()
         .map(compute_v)
-        .collect();
+        .collect::<Result<_, _>>()?;
 
         // sanity check
-        assert_eq!(v_neg.len(), v_pos.len());
-        assert_eq!(v_pos.len(), n + 2 * ext);
+        debug_sanity_check_eq!(v_neg.len(), v_pos.len());
+        debug_sanity_check_eq!(v_pos.len(), n + 2 * ext);
 
-        [Array1::<T>::from(v_neg), Array1::<T>::from(v_pos)]
+        Ok([Array1::<T>::from(v_neg), Array1::<T>::from(v_pos)])
     }
 
     /// # Conservative Finite Difference Schemes
@@ -113,36 +129,453 @@ where
     /// $$
     /// u_{j+1} = u_{j} = \frac{\Delta t}{\Delta x} (h_{j+} - h_{j-})
     /// $$
+    ///
+    /// minus `Simluation::dispersive_update` if the equation is dispersive and
+    /// plus `Simluation::diffusive_update` if it is viscous, i.e. one
+    /// forward-Euler step of `residual`.
+    ///
+    /// Like every `run` method it goes through `run_with_into`, which is the
+    /// one to override for a custom update.
+    fn run(&self, sim: &Simluation<T>, eq: &dyn Equation<T>) -> Result<Array1<T>, SchemeError> {
+        self.run_with(sim, eq, &ForwardEuler)
+    }
+
+    /// Like `run`, but writing the new state into `out` instead of a fresh
+    /// array, as `Simluation::step` does
+    ///
+    /// `out` is only reallocated if its length differs from the grid, so
+    /// reusing it over many steps saves the allocation of the state.
+    fn run_into(
+        &self,
+        sim: &Simluation<T>,
+        eq: &dyn Equation<T>,
+        out: &mut Array1<T>,
+    ) -> Result<(), SchemeError> {
+        self.run_with_into(sim, eq, &ForwardEuler, out)
+    }
+
+    /// Like `run`, but panicking on a CFL violation, e.g. for quick demos
+    fn run_unchecked(&self, sim: &Simluation<T>, eq: &dyn Equation<T>) -> Array1<T> {
+        self.run(sim, eq).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// One time step with the given time integrator, followed by a
+    /// forward-Euler step of `Equation::source`
+    fn run_with(
+        &self,
+        sim: &Simluation<T>,
+        eq: &dyn Equation<T>,
+        integrator: &dyn TimeIntegrator<T>,
+    ) -> Result<Array1<T>, SchemeError> {
+        let mut state = Array1::from(vec![]);
+        self.run_with_into(sim, eq, integrator, &mut state)?;
+        Ok(state)
+    }
+
+    /// Like `run_with`, but writing the new state into `out`
+    ///
+    /// `run`, `run_into`, `run_with` and `Simluation::step` all end up here.
+    fn run_with_into(
+        &self,
+        sim: &Simluation<T>,
+        eq: &dyn Equation<T>,
+        integrator: &dyn TimeIntegrator<T>,
+        out: &mut Array1<T>,
+    ) -> Result<(), SchemeError> {
+        integrator.advance_into(sim, &|stage| self.residual(stage, eq), out)?;
+
+        // source by splitting, at the state after the flux update
+        let (dt, t) = (sim.dt, sim.time());
+        Zip::from(out)
+            .and(&sim.grid)
+            .apply(|u, &x| *u = *u + dt * eq.source(*u, x, t));
+        Ok(())
+    }
 
-    fn run(&self, sim: &Simluation<T>, eq: &dyn Equation<T>) -> Array1<T> {
-        let [h_neg, h_pos] = self.flux(&sim, eq);
-        let u = sim.get_u(0);
+    /// Forward-Euler increment $-\frac{\Delta t}{\Delta x} (h_{j+} - h_{j-})$
+    /// of the current state, including the dispersive and diffusive parts
+    ///
+    /// This is the semi-discrete right-hand side scaled by $\Delta t$, which
+    /// a `TimeIntegrator` evaluates at each of its stages.
+    fn residual(
+        &self,
+        sim: &Simluation<T>,
+        eq: &dyn Equation<T>,
+    ) -> Result<Array1<T>, SchemeError> {
+        let [h_neg, h_pos] = self.mixed_flux(sim, eq)?;
         let dt_over_dx = sim.dt_over_dx();
-        u - (h_pos - h_neg).mapv(|x| dt_over_dx * x)
+        let residual = (h_pos - h_neg).mapv(|x| -(dt_over_dx * x));
+        let residual = match sim.dispersive_update(eq) {
+            Some(dispersion) => residual - dispersion,
+            None => residual,
+        };
+        Ok(match sim.diffusive_update(eq) {
+            Some(diffusion) => residual + diffusion,
+            None => residual,
+        })
+    }
+
+    /// Numerical fluxes $h_{j-}, h_{j+}$ of every cell, from the state and
+    /// $f$ precomputed over the ghost cells of `extended`
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError>;
+
+    /// `flux_with` over the state of `sim`, extended by the `required_ext`
+    /// of the scheme
+    fn flux(
+        &self,
+        sim: &Simluation<T>,
+        eq: &dyn Equation<T>,
+    ) -> Result<[Array1<T>; 2], SchemeError> {
+        self.flux_with(&Extended::new(sim, eq, self.required_ext()))
+    }
+
+    /// Rate of change $-\frac{1}{\Delta x} (h_{j+} - h_{j-})$ of every cell,
+    /// i.e. the semi-discrete right-hand side without the dispersive and
+    /// diffusive parts
+    ///
+    /// It is `residual` divided by $\Delta t$, e.g. for a custom adaptive
+    /// integrator or to locate where the solution changes fastest.
+    fn rhs(&self, sim: &Simluation<T>, eq: &dyn Equation<T>) -> Result<Array1<T>, SchemeError> {
+        let [h_neg, h_pos] = self.mixed_flux(sim, eq)?;
+        let dx = sim.dx;
+        Ok((h_pos - h_neg).mapv(|x| -(x / dx)))
+    }
+
+    /// Modified wavenumber $\tilde{k} \Delta x$ of the spatial stencil at
+    /// $k \Delta x$, for schemes that are linear on advection
+    ///
+    /// On the mode $u_j = e^{i k x_j}$ with $a = 1$ the flux difference is
+    /// $h_{j+} - h_{j-} = i \tilde{k} \Delta x \, u_j$, so the real part is the
+    /// resolved wavenumber (exactly $k \Delta x$ for a perfect stencil) and the
+    /// imaginary part the numerical damping. It approximates the
+    /// semi-discrete limit $\Delta t \to 0$ by a CFL number of
+    /// $\sqrt{\epsilon}$, with $\epsilon$ the machine epsilon, which is
+    /// meaningless for a flux scaled by $\Delta x / \Delta t$ such as
+    /// `LaxFriedrichs`.
+    fn modified_wavenumber(&self, k_dx: T) -> Complex<T>
+    where
+        T: Float + Debug,
+    {
+        let zero = T::from(0).unwrap();
+        let one = T::from(1).unwrap();
+        let eq = Advection { a: one };
+        let dt = T::epsilon().sqrt();
+
+        // wide enough for every stencil to stay clear of the boundaries
+        let half_width = 8;
+        let range = [
+            -T::from(half_width).unwrap(),
+            T::from(half_width + 1).unwrap(),
+        ];
+        let part = |init: &dyn Fn(T) -> T| {
+            let mut sim = Simluation::new(one, dt, range, init);
+            sim.set_boundary(Boundary::Dirichlet([zero; 2]));
+            // never violated at a CFL number of sqrt(eps)
+            let [h_neg, h_pos] = self.flux(&sim, &eq).unwrap();
+            h_pos[half_width] - h_neg[half_width]
+        };
+        let re = part(&|x| (k_dx * x).cos());
+        let im = part(&|x| (k_dx * x).sin());
+
+        // -i (re + i im)
+        Complex::new(im, -re)
+    }
+
+    /// Static properties of the scheme, e.g. for a scheme-selection menu
+    ///
+    /// Defaults to `SchemeInfo::of` named after the type, e.g. `Roe` for
+    /// `Roe { entropy_fix: true }`.
+    fn describe(&self) -> SchemeInfo {
+        let name = format!("{:?}", self);
+        let end = name
+            .find(|c: char| !c.is_alphanumeric())
+            .unwrap_or(name.len());
+        SchemeInfo::of::<T, Self>(self, &name[..end])
+    }
+
+    /// Ghost cells on each side read by `flux_with` and `edge_flux`, which
+    /// the flux evaluations extend the state by
+    ///
+    /// Defaults to 1, the first neighbours. A wider stencil overrides it;
+    /// `describe` measures the actual width by a dry run instead.
+    fn required_ext(&self) -> usize {
+        1
+    }
+
+    /// `SchemeInfo::edge_width`, defaulting to the full stencil everywhere
+    fn edge_width(&self) -> usize {
+        0
+    }
+
+    /// Narrower, lower-order flux used near non-periodic boundaries, see
+    /// `SchemeInfo::edge_width`
+    ///
+    /// Defaults to the upwind flux, which only needs one ghost cell.
+    fn edge_flux(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        serial_upwind(self, extended)
+    }
+
+    /// The `flux` in the interior, with the first and last
+    /// `SchemeInfo::edge_width` interfaces taken from `edge_flux` unless the
+    /// domain is periodic
+    ///
+    /// Interface $k$ is $h_{j-}$ of cell $j = k$ and $h_{j+}$ of cell
+    /// $j = k - 1$, so both sides of an interface are replaced together and
+    /// the scheme stays conservative.
+    ///
+    /// $u$ and $f$ are evaluated once over the `required_ext` ghost cells of
+    /// the scheme and shared by `flux_with`, `speed` and `edge_flux`, see
+    /// `Extended`.
+    fn mixed_flux(
+        &self,
+        sim: &Simluation<T>,
+        eq: &dyn Equation<T>,
+    ) -> Result<[Array1<T>; 2], SchemeError> {
+        let extended = Extended::new(sim, eq, self.required_ext());
+        with_edge_flux(self, &extended, self.edge_width())
+    }
+
+    /// `mixed_flux` over an already extended state, e.g. to combine the
+    /// fluxes of several schemes as `Fct` does
+    fn mixed_flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        with_edge_flux(self, extended, self.edge_width())
+    }
+}
+
+// `flux_with` of `scheme`, with the first and last `width` interfaces taken
+// from `edge_flux` unless the domain is periodic
+fn with_edge_flux<T, S>(
+    scheme: &S,
+    extended: &Extended<T>,
+    width: usize,
+) -> Result<[Array1<T>; 2], SchemeError>
+where
+    T: Numeric,
+    S: Scheme<T> + ?Sized,
+{
+    let sim = extended.sim;
+    let [mut h_neg, mut h_pos] = scheme.flux_with(extended)?;
+    if width == 0 || sim.is_periodic() {
+        return Ok([h_neg, h_pos]);
+    }
+
+    let n = sim.len();
+    let [edge_neg, edge_pos] = scheme.edge_flux(extended)?;
+    let width = width.min(n + 1);
+    for k in (0..width).chain(n + 1 - width..=n) {
+        if k < n {
+            h_neg[k] = edge_neg[k];
+        }
+        if k > 0 {
+            h_pos[k - 1] = edge_pos[k - 1];
+        }
+    }
+
+    Ok([h_neg, h_pos])
+}
+
+/// # Extended State
+///
+/// The state of a simulation extended by `ext` ghost cells on each side,
+/// with $f$ evaluated once over it. `flux_with`, `speed` and `edge_flux` take
+/// views of narrower extensions from it instead of calling `get_u` and
+/// `get_f` again, so an expensive flux is evaluated once per cell and step.
+pub struct Extended<'a, T> {
+    pub sim: &'a Simluation<T>,
+    pub eq: &'a dyn Equation<T>,
+    ext: usize,
+    u: Array1<T>,
+    f: Array1<T>,
+    /// Widest extension requested so far, see `SchemeInfo::of`
+    widest: Cell<usize>,
+}
+
+impl<'a, T> Extended<'a, T>
+where
+    T: Numeric,
+{
+    /// Extend the state of `sim` by `ext` ghost cells and evaluate $f$ of `eq`
+    /// over it, where `ext` must cover every later request
+    pub fn new(sim: &'a Simluation<T>, eq: &'a dyn Equation<T>, ext: usize) -> Self {
+        let u = sim.get_u(ext);
+        let f = eq.f_array(&u);
+        Self {
+            sim,
+            eq,
+            ext,
+            u,
+            f,
+            widest: Cell::new(0),
+        }
+    }
+
+    /// `Simluation::get_u`, as a view of the extended state
+    pub fn u(&self, ext: usize) -> CowArray<'_, T, Ix1> {
+        match self.part(&self.u, ext) {
+            Some(u) => u,
+            None => CowArray::from(self.sim.get_u(ext)),
+        }
+    }
+
+    /// `Simluation::get_f`, as a view of the precomputed $f$
+    pub fn f(&self, ext: usize) -> CowArray<'_, T, Ix1> {
+        match self.part(&self.f, ext) {
+            Some(f) => f,
+            None => CowArray::from(self.sim.get_f(self.eq, ext)),
+        }
+    }
+
+    // the centered part of `full` with `ext` ghost cells, unless wider than computed
+    fn part<'b>(&self, full: &'b Array1<T>, ext: usize) -> Option<CowArray<'b, T, Ix1>> {
+        self.widest.set(self.widest.get().max(ext));
+        debug_assert!(
+            ext <= self.ext,
+            "{} ghost cells requested, but the state was extended by {}",
+            ext,
+            self.ext
+        );
+        let trim = self.ext.checked_sub(ext)?;
+        Some(CowArray::from(full.slice(s![trim..full.len() - trim])))
+    }
+}
+
+/// Descriptor returned by `Scheme::describe`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemeInfo {
+    pub name: String,
+    /// Formal order of accuracy in smooth regions
+    pub order: usize,
+    /// Ghost cells needed on each side, i.e. the widest extension of
+    /// `Extended` that `flux_with` and `edge_flux` read, as measured rather
+    /// than the `Scheme::required_ext` the fluxes are computed with
+    pub required_ext: usize,
+    /// Largest CFL number for which `run` is stable
+    pub cfl_limit: f64,
+    pub is_conservative: bool,
+    /// Number of interfaces next to each non-periodic boundary whose full
+    /// stencil would reach beyond the first ghost cell, which take
+    /// `Scheme::edge_flux` instead. 0 keeps the full stencil everywhere.
+    pub edge_width: usize,
+    /// Whether the scheme is only valid for linear flux, checked by
+    /// `Simluation::validate`
+    pub requires_linear_flux: bool,
+}
+
+impl SchemeInfo {
+    /// Descriptor of a first order, conservative `scheme` stable up to a CFL
+    /// number of 1, with the full stencil up to the boundaries
+    ///
+    /// `required_ext` is measured by a dry run of `flux_with` and `edge_flux`
+    /// on a few cells at rest, recording the widest extension they read, and
+    /// `edge_width` is `Scheme::edge_width`. Override the other fields by
+    /// struct update, e.g.
+    /// `SchemeInfo { order: 2, ..SchemeInfo::of::<T, _>(self, "LaxWendroff") }`.
+    pub fn of<T, S>(scheme: &S, name: &str) -> Self
+    where
+        T: Numeric,
+        S: Scheme<T> + ?Sized,
+    {
+        let zero = T::from(0).unwrap();
+        let one = T::from(1).unwrap();
+        let mut sim = Simluation::new(one, one, [zero, T::from(4).unwrap()], |_| zero);
+        sim.set_boundary(Boundary::Dirichlet([zero; 2]));
+        let extended = Extended::new(&sim, &AtRest, DRY_RUN_EXT);
+
+        // nothing moves at rest, so neither can fail on a CFL condition
+        let _ = scheme.flux_with(&extended);
+        let _ = scheme.edge_flux(&extended);
+
+        Self {
+            name: name.into(),
+            order: 1,
+            required_ext: extended.widest.get(),
+            cfl_limit: 1.,
+            is_conservative: true,
+            edge_width: scheme.edge_width(),
+            requires_linear_flux: false,
+        }
+    }
+}
+
+/// The flux of `Upwind` in a serial loop, so the default `edge_flux` needs no
+/// thread-safe values with the `parallel` feature
+fn serial_upwind<T, S>(scheme: &S, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError>
+where
+    T: Numeric,
+    S: Scheme<T> + ?Sized,
+{
+    let n = extended.sim.len();
+    let zero = T::from(0).unwrap();
+    let f = extended.f(1);
+    let [v_neg, v_pos] = scheme.speed(extended, 0)?;
+    let h_pos = Array1::from_shape_fn(n, |j| if v_pos[j] > zero { f[j + 1] } else { f[j + 2] });
+    let h_neg = Array1::from_shape_fn(n, |j| if v_neg[j] < zero { f[j + 1] } else { f[j] });
+    Ok([h_neg, h_pos])
+}
+
+/// Extension of the dry run of `SchemeInfo::of`, wider than any stencil
+const DRY_RUN_EXT: usize = 8;
+
+/// Zero flux, the equation of the dry run of `SchemeInfo::of`
+#[derive(Debug, Clone)]
+struct AtRest;
+
+impl<T: Numeric> Equation<T> for AtRest {
+    fn f(&self, _u: T) -> T {
+        T::from(0).unwrap()
+    }
+
+    fn df(&self, _u: T) -> T {
+        T::from(0).unwrap()
     }
+}
+
+/// Cloning of boxed schemes, e.g. to reuse one configuration across a sweep
+///
+/// Every `Scheme` gets it from the blanket impl, which is why a scheme must
+/// be `Clone`. The lifetime lets schemes borrowing data be cloned as well.
+pub trait CloneScheme<T> {
+    fn clone_scheme<'a>(&self) -> Box<dyn Scheme<T> + 'a>
+    where
+        Self: 'a;
 
-    fn flux(&self, sim: &Simluation<T>, eq: &dyn Equation<T>) -> [Array1<T>; 2];
+    /// Like `clone_scheme`, but keeping the box shareable between threads
+    fn clone_scheme_sync<'a>(&self) -> Box<dyn Scheme<T> + Send + Sync + 'a>
+    where
+        Self: Send + Sync + 'a;
 }
 
-// pub trait CloneScheme<T> {
-//     fn clone_scheme(&self) -> Box<dyn Scheme<T>>;
-// }
+impl<S, T> CloneScheme<T> for S
+where
+    S: Scheme<T> + Clone,
+    T: Numeric,
+{
+    fn clone_scheme<'a>(&self) -> Box<dyn Scheme<T> + 'a>
+    where
+        Self: 'a,
+    {
+        Box::new(self.clone())
+    }
+
+    fn clone_scheme_sync<'a>(&self) -> Box<dyn Scheme<T> + Send + Sync + 'a>
+    where
+        Self: Send + Sync + 'a,
+    {
+        Box::new(self.clone())
+    }
+}
 
-// impl<S, T> CloneScheme<T> for S
-// where
-//     S: Scheme<T> + Clone + 'static,
-//     T: Float,
-// {
-//     fn clone_scheme(&self) -> Box<dyn Scheme<T>> {
-//         Box::new(self.clone())
-//     }
-// }
+impl<'a, T: 'a> Clone for Box<dyn Scheme<T> + 'a> {
+    fn clone(&self) -> Self {
+        self.clone_scheme()
+    }
+}
 
-// impl<T> Clone for Box<dyn Scheme<T>> {
-//     fn clone(&self) -> Self {
-//         self.clone_scheme()
-//     }
-// }
+impl<'a, T: 'a> Clone for Box<dyn Scheme<T> + Send + Sync + 'a> {
+    fn clone(&self) -> Self {
+        self.clone_scheme_sync()
+    }
+}
 
 /// ## Scheme: Upwind
 ///
@@ -165,51 +598,26 @@ where
 #[derive(Debug, Copy, Clone)]
 pub struct Upwind;
 
-impl<T: Float> Scheme<T> for Upwind {
-    fn flux(&self, sim: &Simluation<T>, eq: &dyn Equation<T>) -> [Array1<T>; 2] {
+impl<T: Numeric + MaybeSync> Scheme<T> for Upwind {
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let sim = extended.sim;
         let ext = 1;
-        let f = sim.get_f(eq, ext);
-        let [v_neg, v_pos] = self.speed(&sim, eq, 0);
+        let n = sim.len();
+        let zero = T::from(0).unwrap();
+        let f = extended.f(ext);
+        let [v_neg, v_pos] = self.speed(extended, 0)?;
 
-        // h_{j+}
-        let h_pos: Vec<T> = izip!(
-            v_pos.iter(),             // v_{j+}
-            f.iter().clone().skip(1), // f_j
-            f.iter().clone().skip(2), // f_{j+1}
-        )
-        .map(
-            |(&v, &f, &f_next)| {
-                if v > T::from(0).unwrap() {
-                    f
-                } else {
-                    f_next
-                }
-            },
-        )
-        .collect();
+        // h_{j+}: f_j or f_{j+1} by v_{j+}
+        let h_pos = parallel::from_fn(n, |j| if v_pos[j] > zero { f[j + 1] } else { f[j + 2] });
 
-        // h_{j-}
-        let h_neg: Vec<T> = izip!(
-            v_neg.iter(),             // v_{j-}
-            f.iter().clone().skip(1), // f_j
-            f.iter().clone(),         // f_{j-1}
-        )
-        .map(
-            |(&v, &f, &f_prev)| {
-                if v < T::from(0).unwrap() {
-                    f
-                } else {
-                    f_prev
-                }
-            },
-        )
-        .collect();
+        // h_{j-}: f_j or f_{j-1} by v_{j-}
+        let h_neg = parallel::from_fn(n, |j| if v_neg[j] < zero { f[j + 1] } else { f[j] });
 
         // sanity check
-        assert_eq!(h_neg.len(), h_pos.len());
-        assert_eq!(h_neg.len(), sim.len());
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), sim.len());
 
-        [Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)]
+        Ok([h_neg, h_pos])
     }
 }
 
@@ -235,15 +643,36 @@ impl<T: Float> Scheme<T> for Upwind {
 #[derive(Debug, Copy, Clone)]
 pub struct BeamWarming;
 
-impl<T: Float> Scheme<T> for BeamWarming {
-    fn flux(&self, sim: &Simluation<T>, eq: &dyn Equation<T>) -> [Array1<T>; 2] {
+impl<T: Numeric> Scheme<T> for BeamWarming {
+    /// The scheme is stable up to a CFL number of 2, but `speed` only
+    /// accepts 1. $h_{\frac{1}{2}}$ and $h_{n-\frac{3}{2}}$ already reach a
+    /// second ghost cell, and the correction is the second-order Taylor term
+    /// of linear advection, $u_{tt} = a^2 u_{xx}$.
+    fn describe(&self) -> SchemeInfo {
+        SchemeInfo {
+            order: 2,
+            requires_linear_flux: true,
+            ..SchemeInfo::of::<T, _>(self, "BeamWarming")
+        }
+    }
+
+    fn required_ext(&self) -> usize {
+        2
+    }
+
+    fn edge_width(&self) -> usize {
+        2
+    }
+
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let sim = extended.sim;
         // f: [n+4]
         let n = sim.len();
         let ext = 2;
-        let f = sim.get_f(eq, ext);
+        let f = extended.f(ext);
 
         // v+, v-: [n+2]
-        let [v_neg, v_pos] = self.speed(&sim, eq, 1);
+        let [v_neg, v_pos] = self.speed(extended, 1)?;
 
         let zero = T::from(0).unwrap();
         let three = T::from(3).unwrap();
@@ -270,10 +699,10 @@ impl<T: Float> Scheme<T> for BeamWarming {
 
         // h_{j-}
         let h_neg: Vec<T> = izip!(
-            v_neg.iter().skip(0),     // v1: v_{(j-)-1}
+            v_neg.iter(),             // v1: v_{(j-)-1}
             v_neg.iter().skip(1),     // v2: v_{j-}
             v_pos.iter().skip(1),     // v3: v_{j+}
-            f.iter().clone().skip(0), // f1: f_{j-2}
+            f.iter().clone(),         // f1: f_{j-2}
             f.iter().clone().skip(1), // f2: f_{j-1}
             f.iter().clone().skip(2), // f2: f_j
             f.iter().clone().skip(3), // f3: f_{j+1}
@@ -289,16 +718,118 @@ impl<T: Float> Scheme<T> for BeamWarming {
         .collect();
 
         // sanity check
-        assert_eq!(h_neg.len(), h_pos.len());
-        assert_eq!(h_neg.len(), n);
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
+
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
+    }
+}
+
+/// ## Scheme: Second-Order Upwind
+///
+/// Starting from the upwind flux at the interface $j+$
+///
+/// $$
+/// h_{j+}^{up} = \begin{cases}
+/// f_j,& v_{j+} > 0 \\\\
+/// f_{j+1},& v_{j+} \le 0
+/// \end{cases}
+/// $$
+///
+/// the upwind neighbour's slope is added, limited by the time step so that
+/// the scheme is exact for linear flux at a CFL number of one
+///
+/// $$
+/// h_{j+} = \begin{cases}
+/// f_j + \frac{1}{2} (1 - v_{j-}) (f_j - f_{j-1}),& v_{j+} > 0 \\\\
+/// f_{j+1} - \frac{1}{2} (1 + v_{(j+)+1}) (f_{j+2} - f_{j+1}),& v_{j+} \le 0
+/// \end{cases}
+/// $$
+///
+/// For linear flux $f = au$ and $v = a \frac{\Delta t}{\Delta x} > 0$ the
+/// update becomes
+///
+/// $$
+/// u_j^{n+1} = u_j - \frac{v}{2} (3u_j - 4u_{j-1} + u_{j-2}) +
+/// \frac{v^2}{2} (u_j - 2u_{j-1} + u_{j-2}),
+/// $$
+///
+/// i.e. the one-sided second-order Taylor expansion of $u(x_j, t + \Delta t)$
+/// using $u_t = -a u_x$ and $u_{tt} = a^2 u_{xx}$. This is the Beam-Warming
+/// scheme in flux form.
+///
+/// With `entropy_fix`, an interface at a transonic rarefaction
+/// $f^{'}(u_j) < 0 < f^{'}(u_{j+1})$ takes the Godunov flux $f(u_s)$ of the
+/// sonic point $f^{'}(u_s) = 0$ instead, which opens the fan that the upwind
+/// choice would keep as a stationary expansion shock.
+
+#[derive(Debug, Copy, Clone)]
+pub struct SecondOrderUpwind {
+    pub entropy_fix: bool,
+}
+
+impl<T: Float> Scheme<T> for SecondOrderUpwind {
+    fn describe(&self) -> SchemeInfo {
+        SchemeInfo {
+            order: 2,
+            ..SchemeInfo::of::<T, _>(self, "SecondOrderUpwind")
+        }
+    }
+
+    fn required_ext(&self) -> usize {
+        3
+    }
+
+    fn edge_width(&self) -> usize {
+        2
+    }
+
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let (sim, eq) = (extended.sim, extended.eq);
+        let n = sim.len();
+        let zero = T::from(0).unwrap();
+        let one = T::from(1).unwrap();
+        let two = T::from(2).unwrap();
+
+        // extended u, f: [n+4]
+        let u = extended.u(2);
+        let f = extended.f(2);
+
+        // v+: [n+4], where the interface j- of cell j is at j+1
+        let [_, v] = self.speed(extended, 2)?;
+
+        // h at the interfaces j- of the cells 0..=n: [n+1]
+        let h: Vec<T> = (0..=n)
+            .map(|j| {
+                // u_{j-1}, u_j
+                let (u_l, u_r) = (u[j + 1], u[j + 2]);
+                if self.entropy_fix && eq.df(u_l) < zero && zero < eq.df(u_r) {
+                    return eq.f(characteristic_state(eq, u_l, u_r, zero));
+                }
+                if v[j + 1] > zero {
+                    // f_{j-1} + (1 - v_{(j-)-1}) / 2 (f_{j-1} - f_{j-2})
+                    f[j + 1] + (one - v[j]) * (f[j + 1] - f[j]) / two
+                } else {
+                    // f_j - (1 + v_{j+}) / 2 (f_{j+1} - f_j)
+                    f[j + 2] - (one + v[j + 2]) * (f[j + 3] - f[j + 2]) / two
+                }
+            })
+            .collect();
+
+        let h_neg: Vec<T> = h.iter().take(n).cloned().collect();
+        let h_pos: Vec<T> = h.iter().skip(1).cloned().collect();
+
+        // sanity check
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
 
-        [Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)]
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
     }
 }
 
 /// ## Scheme: Lax-Wendroff
 ///
-/// The numerical flux is given by
+/// The two-step Richtmyer form, whose numerical flux is given by
 ///
 /// $$
 /// h_{j+} = f\left(\frac{u_{j+1} +
@@ -315,109 +846,1000 @@ impl<T: Float> Scheme<T> for BeamWarming {
 #[derive(Debug, Copy, Clone)]
 pub struct LaxWendroff;
 
-impl<T: Float> Scheme<T> for LaxWendroff {
-    fn flux(&self, sim: &Simluation<T>, eq: &dyn Equation<T>) -> [Array1<T>; 2] {
+impl<T: Numeric + MaybeSync> Scheme<T> for LaxWendroff {
+    fn describe(&self) -> SchemeInfo {
+        SchemeInfo {
+            order: 2,
+            ..SchemeInfo::of::<T, _>(self, "LaxWendroff")
+        }
+    }
+
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let (sim, eq) = (extended.sim, extended.eq);
         let ext = 1;
         let dt_over_dx = sim.dt_over_dx();
         let n = sim.len();
 
         // extended u: [n+2]
-        let u = sim.get_u(ext);
-        let u_iter = u.iter();
+        let u = extended.u(ext);
+
+        // extended f: [n+2]
+        let f = extended.f(ext);
+
+        // u_{j-1/2} at the half step, for j = 0, ..., n
+        let u_half = parallel::from_fn(n + 1, |j| {
+            // u_{j}, u_{j-1}, f_{j}, f_{j-1}
+            let (u, u_prev, f, f_prev) = (u[j + 1], u[j], f[j + 1], f[j]);
+            (u + u_prev - dt_over_dx * (f - f_prev)) / T::from(2).unwrap()
+        });
+        let h = eq.f_array(&u_half);
+
+        // h_{j+}
+        let h_pos = h.slice(s![1..]).to_owned();
+
+        // h_{j-}
+        let h_neg = h.slice(s![..n]).to_owned();
+
+        // sanity check
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), sim.len());
+
+        Ok([h_neg, h_pos])
+    }
+}
+
+/// ## Scheme: Richtmyer
+///
+/// The two-step Richtmyer scheme, under the name it is often looked up by.
+/// It steps exactly like `LaxWendroff`, which is implemented in this form,
+/// but reports itself as "Richtmyer" in `describe`, e.g. in a benchmark or
+/// a sweep.
+#[derive(Debug, Copy, Clone)]
+pub struct Richtmyer;
+
+impl<T: Numeric + MaybeSync> Scheme<T> for Richtmyer {
+    fn describe(&self) -> SchemeInfo {
+        SchemeInfo {
+            order: 2,
+            ..SchemeInfo::of::<T, _>(self, "Richtmyer")
+        }
+    }
+
+    fn required_ext(&self) -> usize {
+        Scheme::<T>::required_ext(&LaxWendroff)
+    }
+
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        LaxWendroff.flux_with(extended)
+    }
+}
+
+/// ## Scheme: Single-Step Lax-Wendroff
+///
+/// The original Taylor-expanded form, with the wave speed at the interface
+/// taken from the spreading speed $v$
+///
+/// $$
+/// h_{j+} = \frac{1}{2} (f_{j} + f_{j+1}) - \frac{1}{2} v_{j+} (f_{j+1} - f_{j})
+/// $$
+///
+/// $$
+/// h_{j-} = \frac{1}{2} (f_{j-1} + f_{j}) - \frac{1}{2} v_{j-} (f_{j} - f_{j-1})
+/// $$
+///
+/// For linear flux it coincides with `LaxWendroff` up to rounding. For
+/// nonlinear flux the two differ by a term of the same order as the
+/// truncation error, so the single-step form is only acceptable while the
+/// solution stays smooth; `Simluation::validate` reports it, and comparing
+/// both with `Simluation::scheme_divergence` tells whether to rely on it.
+
+#[derive(Debug, Copy, Clone)]
+pub struct SingleStepLaxWendroff;
+
+impl<T: Numeric> Scheme<T> for SingleStepLaxWendroff {
+    /// A single wave speed per interface, exact only for linear flux
+    fn describe(&self) -> SchemeInfo {
+        SchemeInfo {
+            order: 2,
+            requires_linear_flux: true,
+            ..SchemeInfo::of::<T, _>(self, "SingleStepLaxWendroff")
+        }
+    }
+
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let sim = extended.sim;
+        let ext = 1;
+        let n = sim.len();
+        let two = T::from(2).unwrap();
 
         // extended f: [n+2]
-        let f = sim.get_f(eq, ext);
+        let f = extended.f(ext);
         let f_iter = f.iter();
 
+        // v+, v-: [n]
+        let [v_neg, v_pos] = self.speed(extended, 0)?;
+
         // h_{j+}
         let h_pos: Vec<T> = izip!(
-            u_iter.clone().skip(1), // u_{j}
-            u_iter.clone().skip(2), // u_{j+1}
+            v_pos.iter(),           // v_{j+}
             f_iter.clone().skip(1), // f_{j}
             f_iter.clone().skip(2), // f_{j+1}
         )
-        .map(|(&u, &u_next, &f, &f_next)| {
-            eq.f((u_next + u - dt_over_dx * (f_next - f)) / T::from(2).unwrap())
-        })
+        .map(|(&v, &f, &f_next)| ((f + f_next) - v * (f_next - f)) / two)
         .collect();
 
         // h_{j-}
         let h_neg: Vec<T> = izip!(
-            u_iter.clone().skip(1), // u_{j}
-            u_iter.clone().take(n), // u_{j-1}
+            v_neg.iter(),           // v_{j-}
             f_iter.clone().skip(1), // f_{j}
             f_iter.clone().take(n), // f_{j-1}
         )
-        .map(|(&u, &u_prev, &f, &f_prev)| {
-            eq.f((u + u_prev - dt_over_dx * (f - f_prev)) / T::from(2).unwrap())
-        })
+        .map(|(&v, &f, &f_prev)| ((f_prev + f) - v * (f - f_prev)) / two)
         .collect();
 
         // sanity check
-        assert_eq!(h_neg.len(), h_pos.len());
-        assert_eq!(h_neg.len(), sim.len());
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
 
-        [Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)]
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
     }
 }
 
-/// ## Scheme: Lax-Friedrichs
+/// ## Scheme: MacCormack
 ///
-/// The numerical flux is given by
+/// The predictor takes a forward difference of the flux
 ///
 /// $$
-/// h_{j+} = \frac{1}{2}(f_{j+1} + f_{j}) - \frac{\Delta x}{2 \Delta t}(u_{j+1} - u_{j})
+/// u^{*}_j = u_j - \frac{\Delta t}{\Delta x} (f_{j+1} - f_{j})
 /// $$
 ///
+/// and the corrector a backward difference of the predicted flux
+/// $f^{*}_j = f(u^{*}_j)$, averaged with the old state
+///
 /// $$
-/// h_{j-} = \frac{1}{2}(f_{j} + f_{j-1}) - \frac{\Delta x}{2 \Delta t}(u_{j} - u_{j-1})
+/// u^{n+1}_j = \frac{1}{2} \left(u_j + u^{*}_j - \frac{\Delta t}{\Delta x} (f^{*}_j - f^{*}_{j-1})\right),
+/// $$
+///
+/// which in flux form reads
+///
+/// $$
+/// h_{j+} = \frac{1}{2} (f_{j+1} + f^{*}_{j})
 /// $$
+///
+/// For linear flux it coincides with `LaxWendroff` up to rounding.
 
 #[derive(Debug, Copy, Clone)]
-pub struct LaxFriedrichs;
+pub struct MacCormack;
 
-impl<T: Float> Scheme<T> for LaxFriedrichs {
-    fn flux(&self, sim: &Simluation<T>, eq: &dyn Equation<T>) -> [Array1<T>; 2] {
+impl<T: Numeric> Scheme<T> for MacCormack {
+    fn describe(&self) -> SchemeInfo {
+        SchemeInfo {
+            order: 2,
+            ..SchemeInfo::of::<T, _>(self, "MacCormack")
+        }
+    }
+
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let (sim, eq) = (extended.sim, extended.eq);
         let ext = 1;
         let dt_over_dx = sim.dt_over_dx();
         let n = sim.len();
+        let two = T::from(2).unwrap();
 
         // extended u: [n+2]
-        let u = sim.get_u(ext);
+        let u = extended.u(ext);
         let u_iter = u.iter();
 
         // extended f: [n+2]
-        let f = sim.get_f(eq, ext);
+        let f = extended.f(ext);
         let f_iter = f.iter();
 
-        // h_{j+}
-        let h_pos: Vec<T> = izip!(
-            u_iter.clone().skip(1), // u_{j}
-            u_iter.clone().skip(2), // u_{j+1}
-            f_iter.clone().skip(1), // f_{j}
-            f_iter.clone().skip(2), // f_{j+1}
+        // h at every interface, h_{j-} of the first cell to h_{j+} of the last: [n+1]
+        let h: Vec<T> = izip!(
+            u_iter.clone(),         // u_j
+            f_iter.clone(),         // f_j
+            f_iter.clone().skip(1), // f_{j+1}
         )
-        .map(|(&u, &u_next, &f, &f_next)| {
-            ((f_next + f) - dt_over_dx * (u_next - u)) / T::from(2).unwrap()
+        .map(|(&u, &f, &f_next)| {
+            let predicted = u - dt_over_dx * (f_next - f);
+            (f_next + eq.f(predicted)) / two
         })
         .collect();
 
-        // h_{j-}
-        let h_neg: Vec<T> = izip!(
-            u_iter.clone().skip(1), // u_{j}
-            u_iter.clone().take(n), // u_{j-1}
-            f_iter.clone().skip(1), // f_{j}
+        let h_neg: Vec<T> = h.iter().take(n).cloned().collect();
+        let h_pos: Vec<T> = h.iter().skip(1).cloned().collect();
+
+        // sanity check
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
+
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
+    }
+}
+
+/// ## Scheme: Third-Order Upwind-Biased
+///
+/// The flux at each interface is reconstructed by the third-order polynomial
+/// through two upwind and one downwind point
+///
+/// $$
+/// h_{j+} = \begin{cases}
+/// \frac{1}{6} (-f_{j-1} + 5f_{j} + 2f_{j+1}),& v_{j+} > 0 \\\\
+/// \frac{1}{6} (2f_{j} + 5f_{j+1} - f_{j+2}),& v_{j+} < 0
+/// \end{cases}
+/// $$
+///
+/// and likewise for $h_{j-}$. Unlike WENO there is no nonlinear weighting,
+/// so it oscillates at discontinuities and is meant for smooth problems.
+/// Forward Euler is unstable for it at any CFL number, so drive it with a
+/// Runge-Kutta method, e.g. `UpwindBiased3.run_with(&sim, &eq, &SspRk3)`.
+
+#[derive(Debug, Copy, Clone)]
+pub struct UpwindBiased3;
+
+impl<T: Numeric> Scheme<T> for UpwindBiased3 {
+    /// Forward Euler is unstable at any CFL number
+    fn describe(&self) -> SchemeInfo {
+        SchemeInfo {
+            order: 3,
+            cfl_limit: 0.,
+            ..SchemeInfo::of::<T, _>(self, "UpwindBiased3")
+        }
+    }
+
+    fn required_ext(&self) -> usize {
+        2
+    }
+
+    fn edge_width(&self) -> usize {
+        2
+    }
+
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let sim = extended.sim;
+        // f: [n+4]
+        let n = sim.len();
+        let ext = 2;
+        let f = extended.f(ext);
+
+        // v+, v-: [n+2]
+        let [_, v_pos] = self.speed(extended, 1)?;
+
+        let zero = T::from(0).unwrap();
+        let two = T::from(2).unwrap();
+        let five = T::from(5).unwrap();
+        let six = T::from(6).unwrap();
+
+        // h at the interfaces j- of the cells 0..=n, i.e. h_{(j-1)+}: [n+1]
+        let h: Vec<T> = izip!(
+            v_pos.iter(),             // v_{(j-1)+}
+            f.iter().clone(),         // f_{j-2}
+            f.iter().clone().skip(1), // f_{j-1}
+            f.iter().clone().skip(2), // f_j
+            f.iter().clone().skip(3), // f_{j+1}
+        )
+        .take(n + 1)
+        .map(|(&v, &f1, &f2, &f3, &f4)| {
+            if v > zero {
+                (five * f2 + two * f3 - f1) / six
+            } else {
+                (two * f2 + five * f3 - f4) / six
+            }
+        })
+        .collect();
+
+        let h_neg: Vec<T> = h.iter().take(n).cloned().collect();
+        let h_pos: Vec<T> = h.iter().skip(1).cloned().collect();
+
+        // sanity check
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
+
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
+    }
+}
+
+/// ## Scheme: Lax-Friedrichs
+///
+/// The numerical flux is given by
+///
+/// $$
+/// h_{j+} = \frac{1}{2}(f_{j+1} + f_{j}) - \frac{\Delta x}{2 \Delta t}(u_{j+1} - u_{j})
+/// $$
+///
+/// $$
+/// h_{j-} = \frac{1}{2}(f_{j} + f_{j-1}) - \frac{\Delta x}{2 \Delta t}(u_{j} - u_{j-1})
+/// $$
+
+#[derive(Debug, Copy, Clone)]
+pub struct LaxFriedrichs;
+
+impl<T: Numeric> Scheme<T> for LaxFriedrichs {
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let sim = extended.sim;
+        let ext = 1;
+        let dx_over_dt = T::from(1).unwrap() / sim.dt_over_dx();
+        let n = sim.len();
+
+        // extended u: [n+2]
+        let u = extended.u(ext);
+        let u_iter = u.iter();
+
+        // extended f: [n+2]
+        let f = extended.f(ext);
+        let f_iter = f.iter();
+
+        // h_{j+}
+        let h_pos: Vec<T> = izip!(
+            u_iter.clone().skip(1), // u_{j}
+            u_iter.clone().skip(2), // u_{j+1}
+            f_iter.clone().skip(1), // f_{j}
+            f_iter.clone().skip(2), // f_{j+1}
+        )
+        .map(|(&u, &u_next, &f, &f_next)| {
+            ((f_next + f) - dx_over_dt * (u_next - u)) / T::from(2).unwrap()
+        })
+        .collect();
+
+        // h_{j-}
+        let h_neg: Vec<T> = izip!(
+            u_iter.clone().skip(1), // u_{j}
+            u_iter.clone().take(n), // u_{j-1}
+            f_iter.clone().skip(1), // f_{j}
             f_iter.clone().take(n), // f_{j-1}
         )
         .map(|(&u, &u_prev, &f, &f_prev)| {
-            ((f + f_prev) - dt_over_dx * (u - u_prev)) / T::from(2).unwrap()
+            ((f + f_prev) - dx_over_dt * (u - u_prev)) / T::from(2).unwrap()
+        })
+        .collect();
+
+        // sanity check
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), sim.len());
+
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
+    }
+}
+
+/// ## Scheme: Rusanov
+///
+/// The local Lax-Friedrichs flux replaces the global $\Delta x / \Delta t$ by
+/// the largest wave speed of the two neighbouring cells
+///
+/// $$
+/// h_{j+} = \frac{1}{2}(f_{j+1} + f_{j}) - \frac{1}{2} \alpha_{j+} (u_{j+1} - u_{j}),
+/// \quad \alpha_{j+} = \max(|f^{'}(u_j)|, |f^{'}(u_{j+1})|)
+/// $$
+///
+/// which adds far less viscosity than `LaxFriedrichs` wherever the local
+/// speed is below the grid speed $\Delta x / \Delta t$.
+
+#[derive(Debug, Copy, Clone)]
+pub struct Rusanov;
+
+impl<T: Float> Scheme<T> for Rusanov {
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let (sim, eq) = (extended.sim, extended.eq);
+        let ext = 1;
+        let n = sim.len();
+        let two = T::from(2).unwrap();
+
+        // extended u: [n+2]
+        let u = extended.u(ext);
+        let u_iter = u.iter();
+
+        // extended f: [n+2]
+        let f = extended.f(ext);
+        let f_iter = f.iter();
+
+        // h at every interface, h_{j-} of the first cell to h_{j+} of the last: [n+1]
+        let h: Vec<T> = izip!(
+            u_iter.clone(),         // u_j
+            u_iter.clone().skip(1), // u_{j+1}
+            f_iter.clone(),         // f_j
+            f_iter.clone().skip(1), // f_{j+1}
+        )
+        .map(|(&u, &u_next, &f, &f_next)| {
+            let alpha = eq.df(u).abs().max(eq.df(u_next).abs());
+            ((f_next + f) - alpha * (u_next - u)) / two
         })
         .collect();
 
+        let h_neg: Vec<T> = h.iter().take(n).cloned().collect();
+        let h_pos: Vec<T> = h.iter().skip(1).cloned().collect();
+
+        // sanity check
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
+
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
+    }
+}
+
+/// ## Scheme: Roe
+///
+/// The flux-difference splitting of Roe upwinds with the averaged wave speed
+///
+/// $$
+/// \hat{a}_{j+} = \begin{cases}
+/// \frac{f_{j+1} - f_{j}}{u_{j+1} - u_{j}},& u_{j} \ne u_{j+1} \\\\
+/// f^{'}(u_j),& u_{j} = u_{j+1}
+/// \end{cases}
+/// $$
+///
+/// and the numerical flux is given by
+///
+/// $$
+/// h_{j+} = \frac{1}{2}(f_{j+1} + f_{j}) - \frac{1}{2} Q(\hat{a}_{j+})(u_{j+1} - u_{j})
+/// $$
+///
+/// where $Q(a) = |a|$. This admits expansion shocks at sonic points, so the
+/// Harten-Hyman entropy fix can be turned on, which replaces $Q$ by
+///
+/// $$
+/// Q(a) = \begin{cases}
+/// |a|,& |a| \ge \delta \\\\
+/// \frac{a^2 + \delta^2}{2 \delta},& |a| < \delta
+/// \end{cases}
+/// $$
+///
+/// with $\delta = \max(0, \hat{a}_{j+} - f^{'}(u_j), f^{'}(u_{j+1}) - \hat{a}_{j+})$.
+
+#[derive(Debug, Copy, Clone)]
+pub struct Roe {
+    pub entropy_fix: bool,
+}
+
+impl<T: Float> Scheme<T> for Roe {
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let (sim, eq) = (extended.sim, extended.eq);
+        let ext = 1;
+        let n = sim.len();
+        let zero = T::from(0).unwrap();
+        let two = T::from(2).unwrap();
+
+        // extended u: [n+2]
+        let u = extended.u(ext);
+        let u_iter = u.iter();
+
+        // extended f: [n+2]
+        let f = extended.f(ext);
+        let f_iter = f.iter();
+
+        // h at every interface, h_{j-} of the first cell to h_{j+} of the last: [n+1]
+        let h: Vec<T> = izip!(
+            u_iter.clone(),         // u_j
+            u_iter.clone().skip(1), // u_{j+1}
+            f_iter.clone(),         // f_j
+            f_iter.clone().skip(1), // f_{j+1}
+        )
+        .map(|(&u, &u_next, &f, &f_next)| {
+            let du = u_next - u;
+            let a = if du == zero {
+                eq.df(u)
+            } else {
+                (f_next - f) / du
+            };
+            let q = if self.entropy_fix {
+                let delta = zero.max(a - eq.df(u)).max(eq.df(u_next) - a);
+                if a.abs() < delta {
+                    (a * a + delta * delta) / (two * delta)
+                } else {
+                    a.abs()
+                }
+            } else {
+                a.abs()
+            };
+            ((f_next + f) - q * du) / two
+        })
+        .collect();
+
+        let h_neg: Vec<T> = h.iter().take(n).cloned().collect();
+        let h_pos: Vec<T> = h.iter().skip(1).cloned().collect();
+
+        // sanity check
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
+
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
+    }
+}
+
+/// ## Scheme: Godunov
+///
+/// The flux is $f$ at $\xi = 0$ of the exact solution to the local Riemann
+/// problem, which for a scalar law reads
+///
+/// $$
+/// h_{j+} = \begin{cases}
+/// \min_{u_j \le u \le u_{j+1}} f(u),& u_j \le u_{j+1} \\\\
+/// \max_{u_{j+1} \le u \le u_j} f(u),& u_j > u_{j+1}
+/// \end{cases}
+/// $$
+///
+/// For a convex or concave flux the extremum lies at an end or at the sonic
+/// point $f^{'}(u_s) = 0$, which is only looked for when $f^{'}$ changes sign
+/// between the states. This resolves the transonic rarefaction without an
+/// entropy fix.
+
+#[derive(Debug, Copy, Clone)]
+pub struct Godunov;
+
+impl<T: Float> Scheme<T> for Godunov {
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let (sim, eq) = (extended.sim, extended.eq);
+        let ext = 1;
+        let n = sim.len();
+
+        // extended u: [n+2]
+        let u = extended.u(ext);
+        let u_iter = u.iter();
+
+        // extended f: [n+2]
+        let f = extended.f(ext);
+        let f_iter = f.iter();
+
+        // h at every interface, h_{j-} of the first cell to h_{j+} of the last: [n+1]
+        let h: Vec<T> = izip!(
+            u_iter.clone(),         // u_j
+            u_iter.clone().skip(1), // u_{j+1}
+            f_iter.clone(),         // f_j
+            f_iter.clone().skip(1), // f_{j+1}
+        )
+        .map(|(&u, &u_next, &f, &f_next)| godunov_flux(eq, [u, u_next], [f, f_next]))
+        .collect();
+
+        let h_neg: Vec<T> = h.iter().take(n).cloned().collect();
+        let h_pos: Vec<T> = h.iter().skip(1).cloned().collect();
+
+        // sanity check
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
+
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
+    }
+}
+
+/// Godunov flux between the states `u` with fluxes `f`, see `Godunov`
+fn godunov_flux<T: Float>(eq: &dyn Equation<T>, [u, u_next]: [T; 2], [f, f_next]: [T; 2]) -> T {
+    let zero = T::from(0).unwrap();
+    let (a, a_next) = (eq.df(u), eq.df(u_next));
+    let sonic = if a < zero && a_next > zero {
+        Some(eq.f(characteristic_state(eq, u, u_next, zero)))
+    } else if a > zero && a_next < zero {
+        Some(eq.f(characteristic_state(eq, u_next, u, zero)))
+    } else {
+        None
+    };
+    let extremum = |pick: fn(T, T) -> T| {
+        sonic.map_or(pick(f, f_next), |f_sonic| pick(pick(f, f_next), f_sonic))
+    };
+    if u <= u_next {
+        extremum(T::min)
+    } else {
+        extremum(T::max)
+    }
+}
+
+/// ## Scheme: MUSCL
+///
+/// The cells are reconstructed as lines with the limited slopes
+/// $\sigma_j = \phi(r_j) (u_{j+1} - u_j)$ of a [`Limiter`], giving the states
+/// on either side of the interface $j+$
+///
+/// $$
+/// u^{L}_{j+} = u_j + \frac{1}{2} \sigma_j, \quad
+/// u^{R}_{j+} = u_{j+1} - \frac{1}{2} \sigma_{j+1},
+/// $$
+///
+/// which are joined by the Godunov flux $h_{j+} = h^{G}(u^{L}_{j+}, u^{R}_{j+})$.
+/// With a TVD limiter the forward-Euler step is TVD up to a CFL number of
+/// $\frac{1}{2}$, while second order in time needs an integrator such as
+/// `SspRk3` through `run_with`.
+
+#[derive(Debug, Copy, Clone)]
+pub struct Muscl<L> {
+    pub limiter: L,
+}
+
+impl<T, L> Scheme<T> for Muscl<L>
+where
+    T: Float,
+    L: Limiter<T> + Clone,
+{
+    fn describe(&self) -> SchemeInfo {
+        SchemeInfo {
+            order: 2,
+            cfl_limit: 0.5,
+            ..SchemeInfo::of::<T, _>(self, &format!("Muscl({:?})", self.limiter))
+        }
+    }
+
+    fn required_ext(&self) -> usize {
+        2
+    }
+
+    /// $h_{\frac{1}{2}}$ needs the slope of the second ghost cell
+    fn edge_width(&self) -> usize {
+        1
+    }
+
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let (sim, eq) = (extended.sim, extended.eq);
+        let n = sim.len();
+        let zero = T::from(0).unwrap();
+        let two = T::from(2).unwrap();
+
+        // extended u: [n+4]
+        let u = extended.u(2);
+
+        // limited slopes of the cells -1..=n: [n+2]
+        let slope: Vec<T> = (1..n + 3)
+            .map(|k| {
+                let forward = u[k + 1] - u[k];
+                if forward == zero {
+                    zero
+                } else {
+                    self.limiter.phi((u[k] - u[k - 1]) / forward) * forward
+                }
+            })
+            .collect();
+
+        // h at every interface, h_{j-} of the first cell to h_{j+} of the last: [n+1]
+        let h: Vec<T> = (0..=n)
+            .map(|i| {
+                let u_left = u[i + 1] + slope[i] / two;
+                let u_right = u[i + 2] - slope[i + 1] / two;
+                godunov_flux(eq, [u_left, u_right], [eq.f(u_left), eq.f(u_right)])
+            })
+            .collect();
+
+        let h_neg: Vec<T> = h.iter().take(n).cloned().collect();
+        let h_pos: Vec<T> = h.iter().skip(1).cloned().collect();
+
+        // sanity check
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
+
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
+    }
+}
+
+/// ## Scheme: Osher-Solomon
+///
+/// The flux integrates the absolute wave speed along the path from $u_j$ to
+/// $u_{j+1}$ in state space
+///
+/// $$
+/// h_{j+} = \frac{1}{2}(f_{j+1} + f_{j}) - \frac{1}{2} \int_{u_j}^{u_{j+1}} |f^{'}(u)| du,
+/// $$
+///
+/// evaluated by the composite Simpson rule over `segments` subintervals. It
+/// is monotone and picks the entropy solution for any scalar flux, including
+/// non-convex ones such as `BuckleyLeverett`, and it is differentiable in
+/// $u_j, u_{j+1}$ unlike the Godunov flux.
+
+#[derive(Debug, Copy, Clone)]
+pub struct OsherSolomon {
+    /// Number of Simpson subintervals, rounded up to an even number
+    pub segments: usize,
+}
+
+impl<T: Float> Scheme<T> for OsherSolomon {
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let (sim, eq) = (extended.sim, extended.eq);
+        let ext = 1;
+        let n = sim.len();
+        let two = T::from(2).unwrap();
+        let segments = self.segments.max(2).div_ceil(2) * 2;
+
+        // extended u: [n+2]
+        let u = extended.u(ext);
+        let u_iter = u.iter();
+
+        // extended f: [n+2]
+        let f = extended.f(ext);
+        let f_iter = f.iter();
+
+        // Simpson rule for the integral of |f'| from a to b
+        let path_integral = |a: T, b: T| {
+            let h = (b - a) / T::from(segments).unwrap();
+            let sum = (0..=segments).fold(T::from(0).unwrap(), |sum, i| {
+                let weight = if i == 0 || i == segments {
+                    1
+                } else if i % 2 == 1 {
+                    4
+                } else {
+                    2
+                };
+                let speed = eq.df(a + h * T::from(i).unwrap()).abs();
+                sum + T::from(weight).unwrap() * speed
+            });
+            sum * h / T::from(3).unwrap()
+        };
+
+        // h at every interface, h_{j-} of the first cell to h_{j+} of the last: [n+1]
+        let h: Vec<T> = izip!(
+            u_iter.clone(),         // u_j
+            u_iter.clone().skip(1), // u_{j+1}
+            f_iter.clone(),         // f_j
+            f_iter.clone().skip(1), // f_{j+1}
+        )
+        .map(|(&u, &u_next, &f, &f_next)| ((f_next + f) - path_integral(u, u_next)) / two)
+        .collect();
+
+        let h_neg: Vec<T> = h.iter().take(n).cloned().collect();
+        let h_pos: Vec<T> = h.iter().skip(1).cloned().collect();
+
         // sanity check
-        assert_eq!(h_neg.len(), h_pos.len());
-        assert_eq!(h_neg.len(), sim.len());
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
+
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
+    }
+}
+
+/// ## Scheme: Flux-Corrected Transport
+///
+/// Blends a monotone low order flux $h^{L}$ with a high order flux $h^{H}$
+/// through the antidiffusive flux $A = h^{H} - h^{L}$,
+///
+/// $$
+/// h_{j+} = h^{L}_{j+} + C_{j+} A_{j+}, \quad 0 \le C_{j+} \le 1,
+/// $$
+///
+/// where the Zalesak limiter picks $C$ so that the result stays within the
+/// local extrema of the old state $u^{n}$ and the transported-diffused state
+///
+/// $$
+/// u^{td}_j = u^{n}_j - \frac{\Delta t}{\Delta x} (h^{L}_{j+} - h^{L}_{j-}).
+/// $$
+///
+/// With the allowed increase $Q^{+}_j = u^{max}_j - u^{td}_j$ and the
+/// incoming antidiffusion
+/// $P^{+}_j = \frac{\Delta t}{\Delta x}(\max(0, A_{j-}) - \min(0, A_{j+}))$,
+/// $R^{+}_j = \min(1, Q^{+}_j / P^{+}_j)$, and $R^{-}_j$ likewise for
+/// decreases. Then
+///
+/// $$
+/// C_{j+} = \begin{cases}
+/// \min(R^{+}_{j+1}, R^{-}_{j}),& A_{j+} \ge 0 \\\\
+/// \min(R^{+}_{j}, R^{-}_{j+1}),& A_{j+} < 0
+/// \end{cases}
+/// $$
+///
+/// At the two boundary interfaces $C$ is the minimum over the first and the
+/// last cell, which keeps periodic runs conservative.
+
+#[derive(Debug, Copy, Clone)]
+pub struct Fct<L, H> {
+    pub low: L,
+    pub high: H,
+}
+
+impl<T, L, H> Scheme<T> for Fct<L, H>
+where
+    T: Float,
+    L: Scheme<T> + Clone,
+    H: Scheme<T> + Clone,
+{
+    /// The order and the name of the high-order scheme, with the stencil and
+    /// CFL limit covering both
+    fn describe(&self) -> SchemeInfo {
+        let low = self.low.describe();
+        let high = self.high.describe();
+        SchemeInfo {
+            order: high.order,
+            cfl_limit: low.cfl_limit.min(high.cfl_limit),
+            is_conservative: low.is_conservative && high.is_conservative,
+            requires_linear_flux: low.requires_linear_flux || high.requires_linear_flux,
+            ..SchemeInfo::of::<T, _>(self, &format!("Fct({}, {})", low.name, high.name))
+        }
+    }
+
+    fn required_ext(&self) -> usize {
+        self.low.required_ext().max(self.high.required_ext())
+    }
+
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let sim = extended.sim;
+        let n = sim.len();
+        let dt_over_dx = sim.dt_over_dx();
+        let zero = T::from(0).unwrap();
+        let one = T::from(1).unwrap();
+
+        let [low_neg, low_pos] = self.low.mixed_flux_with(extended)?;
+        let [high_neg, high_pos] = self.high.mixed_flux_with(extended)?;
+
+        // antidiffusive flux: [n]
+        let a_neg = &high_neg - &low_neg;
+        let a_pos = &high_pos - &low_pos;
+
+        // extended u: [n+2]
+        let u = extended.u(1);
+
+        // transported and diffused u: [n]
+        let u_td = &sim.state - &(&low_pos - &low_neg).mapv(|x| dt_over_dx * x);
+
+        // R+ and R-: [n]
+        let mut r_pos = Vec::with_capacity(n);
+        let mut r_neg = Vec::with_capacity(n);
+        for j in 0..n {
+            // u_{j-1}, u_j, u_{j+1} and whichever of u^td_{j-1}, u^td_{j+1} exist
+            let neighbours = u
+                .iter()
+                .skip(j)
+                .take(3)
+                .chain(u_td.iter().skip(j.max(1) - 1).take(j.min(1) + 2));
+            let u_max = neighbours.clone().fold(u_td[j], |m, &x| m.max(x));
+            let u_min = neighbours.fold(u_td[j], |m, &x| m.min(x));
+
+            let ratio = |q: T, p: T| if p > zero { one.min(q / p) } else { zero };
+            let p_pos = dt_over_dx * (a_neg[j].max(zero) - a_pos[j].min(zero));
+            let p_neg = dt_over_dx * (a_pos[j].max(zero) - a_neg[j].min(zero));
+            r_pos.push(ratio(u_max - u_td[j], p_pos));
+            r_neg.push(ratio(u_td[j] - u_min, p_neg));
+        }
+
+        // C at every interface: [n+1]
+        let c_boundary = [r_pos[0], r_neg[0], r_pos[n - 1], r_neg[n - 1]]
+            .iter()
+            .fold(one, |m, &r| m.min(r));
+        let c: Vec<T> = (0..=n)
+            .map(|k| {
+                if k == 0 || k == n {
+                    c_boundary
+                } else if a_neg[k] >= zero {
+                    r_pos[k].min(r_neg[k - 1])
+                } else {
+                    r_pos[k - 1].min(r_neg[k])
+                }
+            })
+            .collect();
+
+        let h_neg: Vec<T> = (0..n).map(|j| low_neg[j] + c[j] * a_neg[j]).collect();
+        let h_pos: Vec<T> = (0..n).map(|j| low_pos[j] + c[j + 1] * a_pos[j]).collect();
+
+        // sanity check
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
+
+        Ok([Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)])
+    }
+}
+
+/// ## Scheme: Blend
+///
+/// Convex combination of two fluxes
+///
+/// $$
+/// h_{j\pm} = (1 - \theta) h^{L}_{j\pm} + \theta h^{H}_{j\pm},
+/// $$
+///
+/// morphing from a robust scheme at $\theta = 0$ to an accurate one at
+/// $\theta = 1$. Unlike `Fct` the weight is fixed over the domain, but it can
+/// be changed between steps with `set_theta`. It is private so that it stays
+/// within $[0, 1]$.
+
+#[derive(Debug, Clone)]
+pub struct Blend<T: 'static> {
+    pub low: Box<dyn Scheme<T> + Send + Sync>,
+    pub high: Box<dyn Scheme<T> + Send + Sync>,
+    theta: T,
+}
+
+impl<T> Blend<T>
+where
+    T: Float,
+{
+    /// Blend of `low` and `high` with the weight `theta` of `high`, which
+    /// must be within $[0, 1]$
+    pub fn new(
+        low: Box<dyn Scheme<T> + Send + Sync>,
+        high: Box<dyn Scheme<T> + Send + Sync>,
+        theta: T,
+    ) -> Self {
+        let mut blend = Self {
+            low,
+            high,
+            theta: T::from(0).unwrap(),
+        };
+        blend.set_theta(theta);
+        blend
+    }
+
+    /// Weight $\theta$ of `high`
+    pub fn theta(&self) -> T {
+        self.theta
+    }
+
+    /// Change the weight of `high`, which must be within $[0, 1]$
+    pub fn set_theta(&mut self, theta: T) {
+        assert!(
+            theta >= T::from(0).unwrap() && theta <= T::from(1).unwrap(),
+            "theta must be within [0, 1]"
+        );
+        self.theta = theta;
+    }
+}
+
+impl<T> Scheme<T> for Blend<T>
+where
+    T: Float + Debug,
+{
+    /// The order of whichever scheme is switched on alone, otherwise the
+    /// lower one
+    fn describe(&self) -> SchemeInfo {
+        let low = self.low.describe();
+        let high = self.high.describe();
+        let order = if self.theta == T::from(0).unwrap() {
+            low.order
+        } else if self.theta == T::from(1).unwrap() {
+            high.order
+        } else {
+            low.order.min(high.order)
+        };
+        SchemeInfo {
+            order,
+            cfl_limit: low.cfl_limit.min(high.cfl_limit),
+            is_conservative: low.is_conservative && high.is_conservative,
+            requires_linear_flux: low.requires_linear_flux || high.requires_linear_flux,
+            ..SchemeInfo::of::<T, _>(self, &format!("Blend({}, {})", low.name, high.name))
+        }
+    }
+
+    fn required_ext(&self) -> usize {
+        self.low.required_ext().max(self.high.required_ext())
+    }
+
+    fn flux_with(&self, extended: &Extended<T>) -> Result<[Array1<T>; 2], SchemeError> {
+        let sim = extended.sim;
+        let n = sim.len();
+        let theta = self.theta;
+        let one_minus_theta = T::from(1).unwrap() - theta;
+
+        let [low_neg, low_pos] = self.low.mixed_flux_with(extended)?;
+        let [high_neg, high_pos] = self.high.mixed_flux_with(extended)?;
+        let blend = |low: &Array1<T>, high: &Array1<T>| {
+            Array1::from_shape_fn(n, |j| one_minus_theta * low[j] + theta * high[j])
+        };
+        let h_neg = blend(&low_neg, &high_neg);
+        let h_pos = blend(&low_pos, &high_pos);
+
+        // sanity check
+        debug_sanity_check_eq!(h_neg.len(), h_pos.len());
+        debug_sanity_check_eq!(h_neg.len(), n);
+
+        Ok([h_neg, h_pos])
+    }
+}
+
+/// Name of a suitable built-in scheme for the initial data of `sim`
+///
+/// Data is taken as discontinuous if a single jump $|u_{j+1} - u_j|$ spans
+/// more than a quarter of its range $\max u - \min u$, which a smooth profile
+/// only does when it is resolved by a handful of cells. Smooth data gets the
+/// second order `LaxWendroff`, discontinuous data the monotone `Upwind`, or
+/// `Roe` if the flux is nonlinear over the data so the entropy fix can open
+/// rarefactions. The flux counts as linear if $f^{'}$ is the same at every
+/// state, not only at the extremes, which e.g. Buckley-Leverett has in
+/// common between 0 and 1.
+pub fn recommend_scheme<T: Float>(sim: &Simluation<T>, eq: &dyn Equation<T>) -> &'static str {
+    let u = &sim.state;
+    let max = u.iter().cloned().fold(T::neg_infinity(), T::max);
+    let min = u.iter().cloned().fold(T::infinity(), T::min);
+    let max_jump = u
+        .iter()
+        .zip(u.iter().skip(1))
+        .fold(T::from(0).unwrap(), |m, (&l, &r)| m.max((r - l).abs()));
 
-        [Array1::<T>::from(h_neg), Array1::<T>::from(h_pos)]
+    let range = max - min;
+    let discontinuous = range > T::from(0).unwrap() && max_jump > range / T::from(4).unwrap();
+    if !discontinuous {
+        "LaxWendroff"
+    } else if u.iter().all(|&u| eq.df(u) == eq.df(min)) {
+        "Upwind"
+    } else {
+        "Roe"
     }
 }
@@ -0,0 +1,150 @@
+//! # Spectral Initial Conditions
+//!
+//! Initial states built from Fourier modes on a periodic domain, and
+//! localized wave packets for observing how a scheme disperses them, the
+//! Fourier symbol of a scheme, and multi-frequency inflow wave trains.
+
+use crate::base::{Equation, Simluation};
+use crate::boundary::{Boundary, BoundaryKind};
+use crate::schemes::Scheme;
+use num_complex::Complex;
+use num_traits::Float;
+use std::f64::consts::PI;
+
+/// A single mode $A \sin(2 \pi k \frac{x - x_L}{L} + \varphi)$ on a domain
+/// $[x_L, x_L + L)$
+///
+/// The wavenumber counts whole periods over the domain, so every mode is
+/// exactly periodic.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FourierMode<T> {
+    pub amplitude: T,
+    pub wavenumber: usize,
+    pub phase: T,
+}
+
+impl<T> Simluation<T>
+where
+    T: Float,
+{
+    /// Like `new`, with the initial state being the sum of `modes`
+    pub fn from_fourier_modes(dx: T, dt: T, range: [T; 2], modes: &[FourierMode<T>]) -> Self {
+        let two_pi = T::from(2. * PI).unwrap();
+        let length = range[1] - range[0];
+        Self::new(dx, dt, range, |x| {
+            modes.iter().fold(T::from(0).unwrap(), |u, mode| {
+                let k = T::from(mode.wavenumber).unwrap();
+                u + mode.amplitude * (two_pi * k * (x - range[0]) / length + mode.phase).sin()
+            })
+        })
+    }
+}
+
+impl<T> Simluation<T>
+where
+    T: Float,
+{
+    /// Add the wave packet
+    ///
+    /// $$
+    /// e^{-\frac{(x - x_0)^2}{2 \sigma^2}} \cos(k (x - x_0))
+    /// $$
+    ///
+    /// to the state, e.g. onto a zero state as initial data. Its spectrum
+    /// peaks at wavenumber $k$, so tracking it shows the group velocity error
+    /// of a scheme.
+    pub fn inject_wave_packet(&mut self, x0: T, sigma: T, k: T) {
+        let two = T::from(2).unwrap();
+        let packet = self.grid.mapv(|x| {
+            let r = x - x0;
+            (-(r * r) / (two * sigma * sigma)).exp() * (k * r).cos()
+        });
+        let state = &self.state + &packet;
+        self.set_state(state);
+    }
+}
+
+impl<T> Simluation<T>
+where
+    T: Float,
+{
+    /// Amplification factor $g(k)$ of one step of `scheme` on the mode
+    /// $e^{i k x}$, i.e. $u_j^{n+1} = g(k) u_j^{n}$
+    ///
+    /// The scheme is applied to the real and imaginary part separately and
+    /// read off at $x_j = 0$, away from any boundary, so this is only
+    /// meaningful for schemes that are linear on `eq`. Stability requires
+    /// $|g(k)| \le 1$ for every resolved $k$.
+    pub fn scheme_symbol(
+        scheme: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+        dx: T,
+        dt: T,
+        k: T,
+    ) -> Complex<T> {
+        // wide enough for every stencil to stay clear of the boundaries
+        let half_width = 8;
+        let range = [
+            -dx * T::from(half_width).unwrap(),
+            dx * T::from(half_width + 1).unwrap(),
+        ];
+        let center = half_width;
+
+        let part = |init: &dyn Fn(T) -> T| {
+            let mut sim = Self::new(dx, dt, range, init);
+            sim.set_boundary(Boundary::Dirichlet([T::from(0).unwrap(); 2]));
+            scheme.run_unchecked(&sim, eq)[center]
+        };
+        Complex::new(part(&|x| (k * x).cos()), part(&|x| (k * x).sin()))
+    }
+}
+
+/// A single tone $A \sin(2 \pi \omega t + \varphi)$ in time, with the
+/// frequency $\omega$ in periods per unit time
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tone<T> {
+    pub amplitude: T,
+    pub frequency: T,
+    pub phase: T,
+}
+
+/// Inflow signal $\bar{u} + \sum_i A_i \sin(2 \pi \omega_i t + \varphi_i)$,
+/// a band of frequencies for measuring how a scheme transmits each of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaveTrain<T> {
+    pub mean: T,
+    pub tones: Vec<Tone<T>>,
+}
+
+impl<T> WaveTrain<T>
+where
+    T: Float,
+{
+    /// Value of the signal at time `t`
+    pub fn value(&self, t: T) -> T {
+        let two_pi = T::from(2. * PI).unwrap();
+        self.tones.iter().fold(self.mean, |u, tone| {
+            u + tone.amplitude * (two_pi * tone.frequency * t + tone.phase).sin()
+        })
+    }
+}
+
+impl<T> Simluation<T>
+where
+    T: Float,
+{
+    /// Feed `train` at time `t` into the left boundary, call before every step
+    ///
+    /// The right boundary is kept, unless it is periodic. A periodic right side
+    /// would wrap the outflow back onto the inflow, so it is replaced by a
+    /// `Dirichlet` boundary at the mean of the train, which stays in place
+    /// after the inflow ends. Set the right boundary beforehand to keep e.g.
+    /// a `Neumann` outflow there.
+    pub fn set_inflow(&mut self, train: &WaveTrain<T>, t: T) {
+        let right = match self.boundary[1] {
+            BoundaryKind::Periodic => BoundaryKind::Dirichlet(train.mean),
+            right => right,
+        };
+        self.set_boundaries(BoundaryKind::Dirichlet(train.value(t)), right);
+    }
+}
@@ -0,0 +1,105 @@
+//! # Operator Splitting
+//!
+//! Equations with a source term
+//!
+//! $$
+//! u_t + f(u)_x = s(u, x)
+//! $$
+//!
+//! advanced by fractional steps: the conservative scheme first, then the
+//! source over the same time step (Godunov splitting, first order in time).
+
+use crate::base::{Equation, Simluation};
+use crate::error::SchemeError;
+use crate::schemes::Scheme;
+use ndarray::{Array1, Zip};
+use num_traits::Float;
+use std::fmt::{self, Debug};
+
+/// Advances the ODE $u_t = s(u, x)$ of a single cell over `dt`
+///
+/// Any `Fn(u, x, dt) -> u` closure is a source integrator.
+pub trait SourceIntegrator<T> {
+    fn integrate(&self, u: T, x: T, dt: T) -> T;
+}
+
+impl<T, F> SourceIntegrator<T> for F
+where
+    F: Fn(T, T, T) -> T,
+{
+    fn integrate(&self, u: T, x: T, dt: T) -> T {
+        self(u, x, dt)
+    }
+}
+
+/// `eq` with its source left out, which `coupled_step` integrates instead
+#[derive(Clone)]
+struct WithoutSource<'a, T>(&'a dyn Equation<T>);
+
+impl<'a, T> Debug for WithoutSource<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'a, T> Equation<T> for WithoutSource<'a, T>
+where
+    T: Float,
+{
+    fn f(&self, u: T) -> T {
+        self.0.f(u)
+    }
+
+    fn df(&self, u: T) -> T {
+        self.0.df(u)
+    }
+
+    fn f_array(&self, u: &Array1<T>) -> Array1<T> {
+        self.0.f_array(u)
+    }
+
+    fn df_array(&self, u: &Array1<T>) -> Array1<T> {
+        self.0.df_array(u)
+    }
+
+    fn dispersion(&self) -> Option<T> {
+        self.0.dispersion()
+    }
+
+    fn viscosity(&self) -> Option<T> {
+        self.0.viscosity()
+    }
+}
+
+impl<T> Simluation<T>
+where
+    T: Float,
+{
+    /// One `step` of advection by `scheme` followed by the source, returning
+    /// the time reached
+    ///
+    /// The `Equation::source` of `eq`, e.g. the relaxation of
+    /// `RelaxedTraffic`, is left out of the step, as `source` integrates it
+    /// instead.
+    ///
+    /// Fails like `step`, e.g. on a CFL violation, before the source is
+    /// applied, so neither the state nor the time change.
+    pub fn coupled_step(
+        &mut self,
+        scheme: &dyn Scheme<T>,
+        eq: &dyn Equation<T>,
+        source: &dyn SourceIntegrator<T>,
+    ) -> Result<T, SchemeError> {
+        let t = self.step(scheme, &WithoutSource(eq))?;
+        let dt = self.dt;
+        Zip::from(&mut self.state)
+            .and(&self.grid)
+            .apply(|u, &x| *u = source.integrate(*u, x, dt));
+        self.apply_immersed_boundary();
+        // `step` recorded the state before the source
+        if let Some(last) = self.history.as_mut().and_then(|h| h.last_mut()) {
+            last.assign(&self.state);
+        }
+        Ok(t)
+    }
+}
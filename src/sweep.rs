@@ -0,0 +1,87 @@
+//! # Parameter Sweep
+//!
+//! Every combination of equations, initial waves and schemes solved in
+//! parallel with the `parallel` feature, or one after the other without it,
+//! returning the final states and diagnostics instead of plots.
+
+use crate::base::Simluation;
+use crate::error::SchemeError;
+use crate::{BoxedEquation, BoxedFunction, BoxedScheme};
+use itertools::iproduct;
+use ndarray::Array1;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Discretization shared by every run of a sweep
+#[derive(Debug, Copy, Clone)]
+pub struct Domain {
+    pub dx: f64,
+    pub dt: f64,
+    /// Final time
+    pub time: f64,
+    pub space: [f64; 2],
+    /// Keep the state after every step in `SweepResult::history`
+    pub keep_history: bool,
+}
+
+impl Domain {
+    pub fn steps(&self) -> usize {
+        (self.time / self.dt).round() as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SweepResult {
+    pub equation: String,
+    pub init: String,
+    pub scheme: String,
+    /// The simulation at the final time, e.g. for `error_norms` against a
+    /// known exact solution
+    pub sim: Simluation<f64>,
+    pub relative_mass_error: f64,
+    pub total_variation: f64,
+    pub history: Option<Vec<Array1<f64>>>,
+    /// Why the run stopped early, with `sim` holding the last valid state
+    pub error: Option<SchemeError>,
+}
+
+/// Solve every combination in the order of `equations × inits × schemes`,
+/// in parallel with the `parallel` feature
+///
+/// A run failing a step, e.g. on a CFL violation, stops there and reports the
+/// `error`, while the others carry on.
+pub fn run_sweep(
+    equations: &[(&str, BoxedEquation)],
+    inits: &[(&str, BoxedFunction)],
+    schemes: &[(&str, BoxedScheme)],
+    domain: &Domain,
+) -> Vec<SweepResult> {
+    let combinations: Vec<_> = iproduct!(equations.iter(), inits.iter(), schemes.iter()).collect();
+    #[cfg(feature = "parallel")]
+    let combinations = combinations.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let combinations = combinations.into_iter();
+    combinations
+        .map(|((equ_name, equ), (ini_name, ini), (sch_name, sch))| {
+            let mut sim = Simluation::new(domain.dx, domain.dt, domain.space, ini);
+            if domain.keep_history {
+                sim.enable_history();
+            }
+            let error = (0..domain.steps())
+                .try_for_each(|_| sim.step(&**sch, &**equ).map(|_| ()))
+                .err();
+            // moved out rather than kept twice in the result
+            let history = sim.history.take();
+            SweepResult {
+                equation: equ_name.to_string(),
+                init: ini_name.to_string(),
+                scheme: sch_name.to_string(),
+                relative_mass_error: sim.relative_mass_error(),
+                total_variation: sim.total_variation(),
+                sim,
+                history,
+                error,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,254 @@
+//! # Systems of Conservation Laws
+//!
+//! A vector-valued state $\mathbf{u} \in \mathbb{R}^M$ with
+//!
+//! $$
+//! \mathbf{u}_t + \mathbf{F}(\mathbf{u})_x = 0,
+//! $$
+//!
+//! e.g. shallow water or Euler. The state is stored as an $n \times M$ array,
+//! one row per cell, and advanced by the Rusanov flux, which only needs the
+//! largest wave speed instead of a full characteristic decomposition.
+
+use crate::base::half_open_grid;
+use crate::boundary::{Boundary, BoundaryKind};
+use crate::error::{BuildError, DryCellError, SchemeError};
+use ndarray::{Array1, Array2, ArrayView1};
+use num_traits::Float;
+use std::fmt::Debug;
+
+pub trait SystemEquation<T, const M: usize>: Debug {
+    fn flux(&self, u: [T; M]) -> [T; M];
+
+    /// Largest $|\lambda|$ over the eigenvalues of the flux Jacobian
+    fn max_speed(&self, u: [T; M]) -> T;
+
+    /// Reject a state outside the domain of the flux, e.g. a dry shallow
+    /// water cell, before it is evaluated
+    fn admissible(&self, _u: [T; M], _location: usize) -> Result<(), SchemeError> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SystemSimulation<T, const M: usize> {
+    /// $n \times M$, the row $j$ being the state of cell $j$
+    pub state: Array2<T>,
+    pub grid: Array1<T>,
+    dx: T,
+    dt: T,
+    t: T,
+    boundary: [BoundaryKind<[T; M]>; 2],
+}
+
+impl<T, const M: usize> SystemSimulation<T, M>
+where
+    T: Float,
+{
+    /// Periodic system on the half-open grid of `Simluation::new`
+    ///
+    /// Fails on a grid without any cell, i.e. a non-positive `dx` or an
+    /// empty `range`, as every ghost cell refers to a boundary cell.
+    pub fn new<F>(dx: T, dt: T, range: [T; 2], init: F) -> Result<Self, BuildError>
+    where
+        F: Fn(T) -> [T; M],
+    {
+        let zero = T::from(0).unwrap();
+        if dx.is_nan() || dx <= zero {
+            return Err(BuildError::NonPositiveDx(dx.to_f64().unwrap()));
+        }
+        let grid = half_open_grid(dx, range);
+        let n = grid.len();
+        if n == 0 {
+            let range = [range[0].to_f64().unwrap(), range[1].to_f64().unwrap()];
+            return Err(BuildError::EmptyRange(range));
+        }
+        let values: Vec<T> = grid.iter().flat_map(|&x| init(x).to_vec()).collect();
+        Ok(Self {
+            state: Array2::from_shape_vec((n, M), values).unwrap(),
+            grid,
+            dx,
+            dt,
+            t: zero,
+            boundary: [BoundaryKind::Periodic, BoundaryKind::Periodic],
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.nrows()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn dx(&self) -> T {
+        self.dx
+    }
+
+    pub fn dt(&self) -> T {
+        self.dt
+    }
+
+    /// Time reached by `step`, starting from zero
+    pub fn time(&self) -> T {
+        self.t
+    }
+
+    /// State of cell `j`
+    pub fn cell(&self, j: usize) -> [T; M] {
+        to_array(self.state.row(j))
+    }
+
+    /// Replace the boundary condition on both sides
+    ///
+    /// A reflective side mirrors every component alike, so a solid wall
+    /// whose normal velocity must change sign is not covered.
+    pub fn set_boundary(&mut self, boundary: Boundary<[T; M]>) {
+        self.boundary = boundary.sides();
+    }
+
+    /// Discrete mass $\Delta x \sum_j \mathbf{u}_j$ of every component
+    pub fn mass(&self) -> [T; M] {
+        let sum = self.state.sum_axis(ndarray::Axis(0));
+        to_array(sum.view()).map(|m| m * self.dx)
+    }
+
+    /// The state with `ext` ghost rows on each side: $(n + 2 \cdot ext) \times M$
+    pub fn get_u(&self, ext: usize) -> Array2<T> {
+        let n = self.len();
+        let ghost = |side: usize, i: usize| -> [T; M] {
+            match (side, self.boundary[side]) {
+                (_, BoundaryKind::Dirichlet(b)) => b,
+                (0, BoundaryKind::Neumann) => self.cell(0),
+                (_, BoundaryKind::Neumann) => self.cell(n - 1),
+                (0, BoundaryKind::Reflective) => self.cell(i.min(n - 1)),
+                (_, BoundaryKind::Reflective) => self.cell(n - 1 - i.min(n - 1)),
+                (0, BoundaryKind::Periodic) => self.cell(n - 1 - i),
+                (_, BoundaryKind::Periodic) => self.cell(i),
+            }
+        };
+        Array2::from_shape_fn((n + 2 * ext, M), |(r, k)| {
+            if r < ext {
+                ghost(0, ext - 1 - r)[k]
+            } else if r < ext + n {
+                self.state[[r - ext, k]]
+            } else {
+                ghost(1, r - ext - n)[k]
+            }
+        })
+    }
+
+    /// Rusanov flux at every interface, the left one of cell 0 to the right
+    /// one of cell $n - 1$: $(n + 1) \times M$
+    ///
+    /// $$
+    /// \mathbf{h}_{j+} = \frac{1}{2} \left(\mathbf{F}(\mathbf{u}_j) + \mathbf{F}(\mathbf{u}_{j+1})
+    ///     - a_{j+} (\mathbf{u}_{j+1} - \mathbf{u}_j)\right),
+    /// \quad a_{j+} = \max(a(\mathbf{u}_j), a(\mathbf{u}_{j+1}))
+    /// $$
+    ///
+    /// with $a$ the `max_speed`. Fails with the first error of
+    /// `SystemEquation::admissible`, or with `SchemeError::CflViolation` if
+    /// any $a_{j+} \Delta t / \Delta x > 1$.
+    pub fn rusanov_flux(&self, eq: &dyn SystemEquation<T, M>) -> Result<Array2<T>, SchemeError> {
+        let n = self.len();
+        let two = T::from(2).unwrap();
+        let dt_over_dx = self.dt / self.dx;
+
+        // extended u: [n+2]
+        let u = self.get_u(1);
+        for (i, row) in u.outer_iter().enumerate() {
+            eq.admissible(to_array(row), i.saturating_sub(1).min(n - 1))?;
+        }
+        let mut h = Array2::from_elem((n + 1, M), T::from(0).unwrap());
+        for i in 0..=n {
+            let (left, right) = (to_array(u.row(i)), to_array(u.row(i + 1)));
+            let a = eq.max_speed(left).max(eq.max_speed(right));
+            let v = a * dt_over_dx;
+            if v > T::from(1).unwrap() {
+                return Err(SchemeError::CflViolation {
+                    v: v.to_f64().unwrap(),
+                    location: i.min(n - 1),
+                });
+            }
+            let (f_left, f_right) = (eq.flux(left), eq.flux(right));
+            for k in 0..M {
+                h[[i, k]] = (f_left[k] + f_right[k] - a * (right[k] - left[k])) / two;
+            }
+        }
+        Ok(h)
+    }
+
+    /// One forward-Euler step of the Rusanov scheme, returning the new time
+    ///
+    /// The state is left untouched if the step fails.
+    pub fn step(&mut self, eq: &dyn SystemEquation<T, M>) -> Result<T, SchemeError> {
+        let h = self.rusanov_flux(eq)?;
+        let dt_over_dx = self.dt / self.dx;
+        for j in 0..self.len() {
+            for k in 0..M {
+                self.state[[j, k]] = self.state[[j, k]] - dt_over_dx * (h[[j + 1, k]] - h[[j, k]]);
+            }
+        }
+        self.t = self.t + self.dt;
+        Ok(self.t)
+    }
+}
+
+fn to_array<T: Float, const M: usize>(row: ArrayView1<T>) -> [T; M] {
+    let mut u = [T::from(0).unwrap(); M];
+    for (u, &r) in u.iter_mut().zip(row.iter()) {
+        *u = r;
+    }
+    u
+}
+
+/// ## Shallow Water
+///
+/// The state is the height and the discharge $[h, hu]$ with
+///
+/// $$
+/// \begin{bmatrix} h \\\\ hu \end{bmatrix}_t +
+/// \begin{bmatrix} hu \\\\ hu^2 + \frac{1}{2} g h^2 \end{bmatrix}_x = 0
+/// $$
+///
+/// and the wave speeds $u \pm \sqrt{g h}$. A cell with $h \le 0$ is
+/// rejected with a `DryCellError`, wetting and drying are not supported.
+#[derive(Debug, Copy, Clone)]
+pub struct ShallowWater<T> {
+    pub g: T,
+}
+
+impl<T: Float> ShallowWater<T> {
+    /// The two eigenvalues $u - \sqrt{g h} \le u + \sqrt{g h}$
+    pub fn wave_speeds(&self, u: [T; 2]) -> [T; 2] {
+        let velocity = u[1] / u[0];
+        let c = (self.g * u[0]).sqrt();
+        [velocity - c, velocity + c]
+    }
+}
+
+impl<T: Float + Debug> SystemEquation<T, 2> for ShallowWater<T> {
+    fn flux(&self, u: [T; 2]) -> [T; 2] {
+        let half = T::from(0.5).unwrap();
+        [u[1], u[1] * u[1] / u[0] + half * self.g * u[0] * u[0]]
+    }
+
+    fn max_speed(&self, u: [T; 2]) -> T {
+        let [slow, fast] = self.wave_speeds(u);
+        (-slow).max(fast)
+    }
+
+    fn admissible(&self, u: [T; 2], location: usize) -> Result<(), SchemeError> {
+        if u[0] > T::from(0).unwrap() {
+            Ok(())
+        } else {
+            Err(DryCellError {
+                h: u[0].to_f64().unwrap(),
+                location,
+            }
+            .into())
+        }
+    }
+}
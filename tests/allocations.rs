@@ -0,0 +1,116 @@
+use fdm::base::Simluation;
+use fdm::boundary::Boundary;
+use fdm::equations::Advection;
+use fdm::integrators::SspRk3;
+use fdm::schemes::{Scheme, Upwind};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// counts the allocations of the current thread only, as tests run in parallel
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations<F: FnMut()>(mut f: F) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn ghost_cells_into_a_scratch_buffer_match_and_skip_allocation() {
+    let dx = 1. / 64.;
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| x * x);
+    let mut scratch = vec![];
+    for &boundary in [
+        Boundary::Periodic,
+        Boundary::Dirichlet([0.5, -0.5]),
+        Boundary::Neumann,
+        Boundary::Reflective,
+    ]
+    .iter()
+    {
+        sim.set_boundary(boundary);
+        for ext in 0..=3 {
+            sim.get_u_into(ext, &mut scratch);
+            assert_eq!(scratch, sim.get_u(ext).to_vec());
+        }
+    }
+
+    let steps = 100;
+    let allocating = allocations(|| {
+        for _ in 0..steps {
+            assert_eq!(sim.get_u(3).len(), sim.len() + 6);
+        }
+    });
+    let buffered = allocations(|| {
+        for _ in 0..steps {
+            sim.get_u_into(3, &mut scratch);
+            assert_eq!(scratch.len(), sim.len() + 6);
+        }
+    });
+    assert!(allocating >= steps, "{} allocations", allocating);
+    assert_eq!(buffered, 0);
+}
+
+#[test]
+fn step_reuses_the_previous_state() {
+    let dx = 1. / 64.;
+    let eq = Advection { a: 1.0 };
+    let mut stepped = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| x * x);
+    let mut replaced = stepped.clone();
+    // the first step allocates the spare state
+    stepped.step(&Upwind, &eq).unwrap();
+    replaced.set_state(Upwind.run(&replaced, &eq).unwrap());
+
+    let steps = 100;
+    let stepping = allocations(|| {
+        for _ in 0..steps {
+            stepped.step(&Upwind, &eq).unwrap();
+        }
+    });
+    let running = allocations(|| {
+        for _ in 0..steps {
+            replaced.set_state(Upwind.run(&replaced, &eq).unwrap());
+        }
+    });
+    assert_eq!(stepped.state, replaced.state);
+    assert!(running >= stepping + steps, "{} vs {}", running, stepping);
+}
+
+#[test]
+fn runge_kutta_stages_do_not_copy_the_history() {
+    let dx = 1. / 64.;
+    let eq = Advection { a: 1.0 };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| x * x);
+    // the first run may set up a thread pool with the `parallel` feature
+    Upwind.run_with(&sim, &eq, &SspRk3).unwrap();
+    let fresh = allocations(|| {
+        Upwind.run_with(&sim, &eq, &SspRk3).unwrap();
+    });
+
+    sim.enable_history();
+    for _ in 0..100 {
+        sim.step(&Upwind, &eq).unwrap();
+    }
+    let long_history = allocations(|| {
+        Upwind.run_with(&sim, &eq, &SspRk3).unwrap();
+    });
+    assert_eq!(long_history, fresh);
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "gif")]
+
+use fdm::animation::GifWriter;
+use fdm::base::Simluation;
+use fdm::equations::Advection;
+use fdm::schemes::{Scheme, Upwind};
+use std::f64::consts::PI;
+
+#[test]
+fn streams_every_frame_into_a_valid_gif() {
+    let mut sim = Simluation::new(1e-2, 6e-3, [-1., 1.], |x: f64| (PI * x).sin());
+    let mut gif = GifWriter::new(vec![], 120, 90, [-1.5, 1.5]).unwrap();
+    for _ in 0..100 {
+        sim.set_state(Upwind.run(&sim, &Advection { a: 1.0 }).unwrap());
+        sim.frame_to_gif(&mut gif).unwrap();
+    }
+    assert_eq!(gif.frames(), 100);
+    let bytes = gif.finish().unwrap();
+
+    let mut decoder = gif::DecodeOptions::new().read_info(&bytes[..]).unwrap();
+    let mut frames = 0;
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        assert_eq!((frame.width, frame.height), (120, 90));
+        frames += 1;
+    }
+    assert_eq!(frames, 100);
+}
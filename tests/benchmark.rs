@@ -0,0 +1,148 @@
+use fdm::base::Simluation;
+use fdm::benchmark::{
+    benchmark, convergence_study, error_norms, BenchmarkReport, ExactSolution, PeriodicAdvection,
+    ValidationProblem,
+};
+use fdm::equations::Advection;
+use fdm::error::SchemeError;
+use fdm::schemes::{Extended, LaxWendroff, Scheme, Upwind};
+use ndarray::Array1;
+use std::f64::consts::PI;
+
+#[test]
+fn report_ranks_second_order_scheme_first() {
+    let dx = 1. / 32.;
+    let problem = ValidationProblem {
+        equation: &Advection { a: 1.0 },
+        init: &|x: f64| (PI * x).sin(),
+        exact: &|x: f64, t: f64| (PI * (x - t)).sin(),
+        dx,
+        dt: 0.5 * dx,
+        range: [-1., 1.],
+        steps: 32,
+    };
+
+    let mut report = benchmark(&[&Upwind, &LaxWendroff], &problem);
+    let names = |report: &BenchmarkReport| -> Vec<String> {
+        report.entries.iter().map(|e| e.name.clone()).collect()
+    };
+    assert_eq!(names(&report), vec!["Upwind", "LaxWendroff"]);
+
+    report.sort_by_accuracy();
+    assert_eq!(names(&report), vec!["LaxWendroff", "Upwind"]);
+    for entry in report.entries.iter() {
+        let errors = entry.errors.as_ref().unwrap();
+        assert!(errors.l1 <= errors.l2 * 2f64.sqrt() + 1e-15);
+        assert!(errors.l2 <= errors.linf * 2f64.sqrt() + 1e-15);
+    }
+    assert_eq!(report.to_string().lines().count(), 3);
+}
+
+// `Upwind` until a quarter of the run, then a CFL violation
+#[derive(Debug, Clone)]
+struct Unstable;
+
+impl Scheme<f64> for Unstable {
+    fn flux_with(&self, extended: &Extended<f64>) -> Result<[Array1<f64>; 2], SchemeError> {
+        if extended.sim.time() < 0.125 {
+            Upwind.flux_with(extended)
+        } else {
+            Err(SchemeError::CflViolation { v: 2., location: 0 })
+        }
+    }
+}
+
+#[test]
+fn an_unstable_scheme_is_reported_without_stopping_the_others() {
+    let dx = 1. / 32.;
+    let problem = ValidationProblem {
+        equation: &Advection { a: 1.0 },
+        init: &|x: f64| (PI * x).sin(),
+        exact: &|x: f64, t: f64| (PI * (x - t)).sin(),
+        dx,
+        dt: 0.5 * dx,
+        range: [-1., 1.],
+        steps: 32,
+    };
+
+    let mut report = benchmark(&[&Unstable, &Upwind], &problem);
+    report.sort_by_accuracy();
+    assert_eq!(report.entries[0].name, "Upwind");
+    assert!(report.entries[0].errors.is_ok());
+    assert_eq!(report.entries[1].name, "Unstable");
+    match &report.entries[1].errors {
+        Err(SchemeError::CflViolation { .. }) => {}
+        other => panic!("{:?}", other),
+    }
+    assert!(report
+        .to_string()
+        .lines()
+        .last()
+        .unwrap()
+        .contains("failed"));
+}
+
+#[test]
+fn periodic_advection_is_the_wrapped_initial_profile() {
+    let exact = PeriodicAdvection {
+        a: 1.0,
+        range: [-1., 1.],
+        init: |x: f64| x,
+    };
+    assert_eq!(exact.eval(0.5, 0.), 0.5);
+    assert!((exact.eval(0.5, 0.25) - 0.25).abs() < 1e-15);
+    assert!((exact.eval(-0.75, 0.5) - 0.75).abs() < 1e-15);
+    assert!((exact.eval(0.5, 4.25) - 0.25).abs() < 1e-12);
+}
+
+#[test]
+fn error_norms_shrink_with_the_order_of_the_scheme() {
+    let exact = PeriodicAdvection {
+        a: 1.0,
+        range: [-1., 1.],
+        init: |x: f64| (PI * x).sin(),
+    };
+    let errors = |scheme: &dyn Scheme<f64>, dx: f64| {
+        let dt = 0.5 * dx;
+        let mut sim = Simluation::new(dx, dt, [-1., 1.], |x| exact.eval(x, 0.));
+        let steps = (1. / dt).round() as usize;
+        for _ in 0..steps {
+            sim.step(scheme, &Advection { a: 1.0 }).unwrap();
+        }
+        error_norms(&sim, &exact, sim.time())
+    };
+    let ratio =
+        |scheme: &dyn Scheme<f64>| errors(scheme, 1. / 32.).l1 / errors(scheme, 1. / 64.).l1;
+    assert!(ratio(&Upwind) > 1.8 && ratio(&Upwind) < 2.2);
+    assert!(ratio(&LaxWendroff) > 3.6 && ratio(&LaxWendroff) < 4.4);
+
+    let norms = errors(&Upwind, 1. / 32.);
+    assert!(norms.l1 <= norms.l2 * 2f64.sqrt() && norms.l2 <= norms.linf * 2f64.sqrt());
+}
+
+#[test]
+fn convergence_study_recovers_the_order_of_each_scheme() {
+    let exact = PeriodicAdvection {
+        a: 1.0,
+        range: [-1., 1.],
+        init: |x: f64| (PI * x).sin(),
+    };
+    let study = |scheme: &dyn Scheme<f64>| {
+        convergence_study(
+            scheme,
+            &Advection { a: 1.0 },
+            &exact,
+            [-1., 1.],
+            0.5,
+            &[32, 64, 128],
+            1.0,
+        )
+        .unwrap()
+    };
+    let upwind = study(&Upwind);
+    assert_eq!(upwind.samples.len(), 3);
+    assert_eq!(upwind.samples[1].0, 1. / 32.);
+    assert!((upwind.order - 1.).abs() < 0.15, "{:?}", upwind);
+    let lax_wendroff = study(&LaxWendroff);
+    assert!((lax_wendroff.order - 2.).abs() < 0.15, "{:?}", lax_wendroff);
+}
@@ -0,0 +1,74 @@
+use fdm::boundary::{Boundary, BoundaryKind};
+use fdm::builder::SimulationBuilder;
+use fdm::equations::Advection;
+use fdm::error::BuildError;
+
+fn base() -> SimulationBuilder<'static, f64> {
+    SimulationBuilder::new().range([-1., 1.]).dx(0.1)
+}
+
+#[test]
+fn builder_matches_positional_constructor() {
+    let sim = base()
+        .dt(0.05)
+        .boundary(Boundary::Dirichlet([1., 0.]))
+        .init(|x| x * x)
+        .build()
+        .unwrap();
+    assert_eq!(sim.len(), 20);
+    assert_eq!(sim.dt_over_dx(), 0.5);
+    assert_eq!(sim.state[0], 1.);
+    assert_eq!(
+        sim.boundaries(),
+        [BoundaryKind::Dirichlet(1.), BoundaryKind::Dirichlet(0.)]
+    );
+    assert!(sim.equation().is_none());
+}
+
+#[test]
+fn builder_derives_dt_from_cfl_and_equation() {
+    let unit = base().cfl(0.8).build().unwrap();
+    assert!((unit.dt_over_dx() - 0.8).abs() < 1e-15);
+
+    let sim = base()
+        .cfl(0.8)
+        .equation(Box::new(Advection { a: 2.0 }))
+        .build()
+        .unwrap();
+    assert!((sim.dt_over_dx() - 0.4).abs() < 1e-15);
+    assert!(sim.equation().is_some());
+    assert!(sim.state.iter().all(|&u| u == 0.));
+}
+
+#[test]
+fn builder_rejects_incomplete_or_invalid_setups() {
+    let error = |builder: SimulationBuilder<'static, f64>| builder.build().unwrap_err();
+    assert_eq!(
+        error(SimulationBuilder::new().dx(0.1).dt(0.1)),
+        BuildError::MissingRange
+    );
+    assert_eq!(
+        error(base().range([1., -1.]).dt(0.1)),
+        BuildError::EmptyRange([1., -1.])
+    );
+    assert_eq!(
+        error(SimulationBuilder::new().range([-1., 1.]).dt(0.1)),
+        BuildError::MissingDx
+    );
+    assert_eq!(
+        error(base().dx(-0.1).dt(0.1)),
+        BuildError::NonPositiveDx(-0.1)
+    );
+    assert_eq!(error(base().dt(0.)), BuildError::NonPositiveDt(0.));
+    assert_eq!(error(base().dt(-0.1)), BuildError::NonPositiveDt(-0.1));
+    assert_eq!(error(base().cfl(-0.5)), BuildError::NonPositiveCfl(-0.5));
+    assert!(matches!(
+        error(base().cfl(f64::NAN)),
+        BuildError::NonPositiveCfl(cfl) if cfl.is_nan()
+    ));
+    assert_eq!(error(base()), BuildError::MissingTimeStep);
+    assert_eq!(
+        error(base().dt(0.1).cfl(0.5)),
+        BuildError::ConflictingTimeStep
+    );
+}
@@ -0,0 +1,99 @@
+use fdm::base::{Equation, Simluation};
+use fdm::check::{self, CheckMode};
+use fdm::equations::Advection;
+use fdm::error::SchemeError;
+use fdm::integrators::TimeIntegrator;
+use fdm::schemes::{Extended, Scheme, Upwind};
+use ndarray::Array1;
+
+fn short_state(sim: &mut Simluation<f64>) {
+    let len = sim.len();
+    sim.set_state(Array1::zeros(len - 1));
+}
+
+#[test]
+fn failed_checks_warn_instead_of_panicking() {
+    let mut sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| x);
+    let before = sim.state.clone();
+
+    check::set_check_mode(CheckMode::Warn);
+    short_state(&mut sim);
+    check::set_check_mode(CheckMode::Panic);
+
+    // the bad state is rejected
+    assert_eq!(sim.state, before);
+    let warnings = check::take_warnings();
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert!(warnings[0].contains("sanity check failed"));
+    assert!(check::take_warnings().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "sanity check failed")]
+fn failed_checks_panic_by_default() {
+    assert_eq!(check::check_mode(), CheckMode::Panic);
+    let mut sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| x);
+    short_state(&mut sim);
+}
+
+#[test]
+fn stage_and_mass_fix_checks_honor_the_mode() {
+    let mut sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| x);
+    let before = sim.state.clone();
+    let short = Array1::zeros(sim.len() - 1);
+
+    check::set_check_mode(CheckMode::Warn);
+    let stage = sim.rk_substep(&short, 0.5);
+    let leftover = sim.flux_limited_mass_fix(&short, [-1., 1.]);
+    check::set_check_mode(CheckMode::Panic);
+
+    assert_eq!(stage, before);
+    assert_eq!(leftover, 0.);
+    assert_eq!(sim.state, before);
+    assert_eq!(check::take_warnings().len(), 2);
+}
+
+// an update one cell short
+#[derive(Debug, Clone)]
+struct Shrinking;
+
+impl Scheme<f64> for Shrinking {
+    fn flux_with(&self, extended: &Extended<f64>) -> Result<[Array1<f64>; 2], SchemeError> {
+        Upwind.flux_with(extended)
+    }
+
+    fn run_with_into(
+        &self,
+        sim: &Simluation<f64>,
+        _eq: &dyn Equation<f64>,
+        _integrator: &dyn TimeIntegrator<f64>,
+        out: &mut Array1<f64>,
+    ) -> Result<(), SchemeError> {
+        *out = Array1::zeros(sim.len() - 1);
+        Ok(())
+    }
+}
+
+#[test]
+fn step_fails_on_a_state_of_the_wrong_length_without_advancing() {
+    let mut sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| x);
+    sim.enable_history();
+    let before = sim.state.clone();
+
+    check::set_check_mode(CheckMode::Warn);
+    let t = sim.step(&Shrinking, &Advection { a: 1.0 });
+    check::set_check_mode(CheckMode::Panic);
+
+    let n = sim.len();
+    assert_eq!(
+        t,
+        Err(SchemeError::LengthMismatch {
+            grid: n,
+            state: n - 1
+        })
+    );
+    assert_eq!(sim.state, before);
+    assert_eq!(sim.time(), 0.);
+    assert!(sim.history().is_empty());
+    assert_eq!(check::take_warnings().len(), 1);
+}
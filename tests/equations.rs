@@ -0,0 +1,241 @@
+use fdm::base::{ConservationLaw, Convexity, Equation, Simluation, StepMode};
+use fdm::boundary::Boundary;
+use fdm::equations::{
+    Advection, BuckleyLeverett, InviscidBurger, RelaxedTraffic, Traffic, ViscousBurger, WarmCache,
+};
+use fdm::schemes::{BeamWarming, LaxWendroff, Roe, Scheme, Upwind};
+use fdm::BoxedEquation;
+use std::cell::Cell;
+use std::f64::consts::PI;
+
+// only compiles for fully specified laws
+fn describe<E: ConservationLaw<f64>>(eq: &E, u: f64) -> (Convexity, Vec<f64>, f64, f64) {
+    (
+        eq.convexity(),
+        eq.eigenvalues(u),
+        eq.entropy(u).unwrap(),
+        eq.entropy_flux(u).unwrap(),
+    )
+}
+
+#[test]
+fn built_in_equations_are_conservation_laws() {
+    assert_eq!(
+        describe(&Advection { a: 2.0 }, 3.),
+        (Convexity::Linear, vec![2.], 4.5, 9.)
+    );
+    assert_eq!(
+        describe(&InviscidBurger, 3.),
+        (Convexity::Convex, vec![3.], 4.5, 9.)
+    );
+    assert_eq!(
+        describe(&ViscousBurger { nu: 0.1 }, 3.),
+        (Convexity::Convex, vec![3.], 4.5, 9.)
+    );
+
+    let relaxed = RelaxedTraffic {
+        traffic: Traffic {
+            v_max: 1.0,
+            rho_max: 1.0,
+        },
+        tau: 0.1,
+        equilibrium: |_: f64| 0.5,
+    };
+    assert_eq!(relaxed.convexity(), Convexity::Concave);
+    assert_eq!(relaxed.eigenvalues(0.25), vec![0.5]);
+}
+
+#[test]
+fn buckley_leverett_flux_and_derivative_agree() {
+    for &m in [0.5f64, 1., 2.].iter() {
+        let eq = BuckleyLeverett { mobility_ratio: m };
+        assert_eq!(eq.f(0.), 0.);
+        assert_eq!(eq.f(1.), 1.);
+        assert_eq!(eq.convexity(), Convexity::NonConvex);
+        let h = 1e-6;
+        for &u in [0.1, 0.3, 0.5, 0.7, 0.9].iter() {
+            let fd = (eq.f(u + h) - eq.f(u - h)) / (2. * h);
+            assert!((eq.df(u) - fd).abs() < 1e-8, "m = {}, u = {}", m, u);
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct Counted {
+    calls: Cell<usize>,
+}
+
+impl Equation<f64> for Counted {
+    fn f(&self, u: f64) -> f64 {
+        self.calls.set(self.calls.get() + 1);
+        u * u / 2.
+    }
+
+    fn df(&self, u: f64) -> f64 {
+        u
+    }
+}
+
+#[test]
+fn warm_cache_evaluates_flux_once_per_cell_per_step() {
+    let init = |x: f64| 0.5 * (PI * x).sin();
+    let mut plain = Simluation::new(0.1, 0.05, [-1., 1.], init);
+    let mut warmed = Simluation::new(0.1, 0.05, [-1., 1.], init);
+    let counted = Counted::default();
+    let mut cache = WarmCache::new(&counted);
+
+    let steps = 5;
+    for _ in 0..steps {
+        cache.warm(&warmed, 1);
+        warmed.set_state(Upwind.run(&warmed, &cache).unwrap());
+        plain.set_state(Upwind.run(&plain, &InviscidBurger).unwrap());
+    }
+
+    // Upwind needs one ghost cell on each side
+    assert_eq!(counted.calls.get(), steps * (warmed.len() + 2));
+    assert_eq!(warmed.state, plain.state);
+}
+
+#[test]
+fn run_evaluates_flux_once_per_cell_of_the_widest_stencil() {
+    let sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| 0.5 * (PI * x).sin());
+    let schemes: [&dyn Scheme<f64>; 2] = [&Upwind, &BeamWarming];
+    for &scheme in schemes.iter() {
+        let counted = Counted::default();
+        let shared = scheme.run(&sim, &counted).unwrap();
+        let ext = scheme.describe().required_ext;
+        assert_eq!(counted.calls.get(), sim.len() + 2 * ext, "{:?}", scheme);
+        assert_eq!(shared, scheme.run(&sim, &InviscidBurger).unwrap());
+    }
+}
+
+#[derive(Debug, Clone)]
+struct KdvBurger {
+    mu: f64,
+}
+
+impl Equation<f64> for KdvBurger {
+    fn f(&self, u: f64) -> f64 {
+        u * u / 2.
+    }
+
+    fn df(&self, u: f64) -> f64 {
+        u
+    }
+
+    fn dispersion(&self) -> Option<f64> {
+        Some(self.mu)
+    }
+}
+
+#[test]
+fn dispersion_produces_oscillations_at_steepening_front() {
+    let dx = 1. / 64.;
+    let front = |x: f64| 0.5 * (1. - (x / 0.1).tanh());
+    let mut plain = Simluation::new(dx, 0.5 * dx, [-1., 1.], front);
+    let mut dispersive = Simluation::new(dx, 0.5 * dx, [-1., 1.], front);
+    plain.set_boundary(Boundary::Dirichlet([1., 0.]));
+    dispersive.set_boundary(Boundary::Dirichlet([1., 0.]));
+
+    let eq = KdvBurger { mu: 1e-4 };
+    for _ in 0..40 {
+        plain.set_state(Upwind.run(&plain, &InviscidBurger).unwrap());
+        dispersive.set_state(Upwind.run(&dispersive, &eq).unwrap());
+    }
+
+    let overshoot = |sim: &Simluation<f64>| sim.state.iter().fold(0., |m: f64, u| m.max(u - 1.));
+    assert!(overshoot(&plain) < 1e-12);
+    assert!(overshoot(&dispersive) > 1e-3, "{}", overshoot(&dispersive));
+}
+
+#[test]
+fn viscous_burgers_keeps_its_traveling_wave() {
+    let dx = 1. / 64.;
+    let nu = 0.05;
+    // u_L = 1 and u_R = 0, moving at s = 1/2
+    let wave = |x: f64, t: f64| 1. / (1. + ((x - 0.5 * t) / (2. * nu)).exp());
+    // nu dt / dx^2 = 0.32
+    let dt = 0.1 * dx;
+    let mut viscous = Simluation::new(dx, dt, [-1., 1.], |x| wave(x, 0.));
+    viscous.set_boundary(Boundary::Dirichlet([1., 0.]));
+    let mut inviscid = viscous.clone();
+
+    let roe = Roe { entropy_fix: true };
+    viscous
+        .run_until(1., &roe, &ViscousBurger { nu }, StepMode::Fixed)
+        .unwrap();
+    inviscid
+        .run_until(1., &roe, &InviscidBurger, StepMode::Fixed)
+        .unwrap();
+    let error = |sim: &Simluation<f64>| sim.error_norms(&|x| wave(x, 1.)).linf;
+    assert!(error(&viscous) < 0.02, "{}", error(&viscous));
+    assert!(error(&inviscid) > 0.1, "{}", error(&inviscid));
+}
+
+#[derive(Debug, Clone)]
+struct DecayingAdvection {
+    k: f64,
+}
+
+impl Equation<f64> for DecayingAdvection {
+    fn f(&self, u: f64) -> f64 {
+        u
+    }
+
+    fn df(&self, _: f64) -> f64 {
+        1.
+    }
+
+    fn source(&self, u: f64, _: f64, _: f64) -> f64 {
+        -self.k * u
+    }
+}
+
+#[test]
+fn linear_decay_source_damps_the_amplitude_exponentially() {
+    let dx = 1. / 64.;
+    let eq = DecayingAdvection { k: 0.5 };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    let amplitude = |sim: &Simluation<f64>| sim.state.iter().fold(0., |m: f64, u| m.max(u.abs()));
+    for &t in [0.5, 1., 2.].iter() {
+        sim.run_until(t, &LaxWendroff, &eq, StepMode::Fixed)
+            .unwrap();
+        let expected = (-eq.k * t).exp();
+        assert!(
+            (amplitude(&sim) / expected - 1.).abs() < 0.01,
+            "{} vs {} at t = {}",
+            amplitude(&sim),
+            expected,
+            t
+        );
+    }
+    assert_eq!(Advection { a: 1.0 }.source(2., 0., 0.), 0.);
+}
+
+#[test]
+fn boxed_equations_clone_into_equal_copies() {
+    let boxed: BoxedEquation = Box::new(Advection { a: -0.5 });
+    let plain: Box<dyn Equation<f64>> = Box::new(BuckleyLeverett { mobility_ratio: 2. });
+    let (boxed_copy, plain_copy) = (boxed.clone(), plain.clone());
+    for &u in [-1., 0., 0.3, 0.7, 2.].iter() {
+        assert_eq!(boxed.f(u), boxed_copy.f(u));
+        assert_eq!(boxed.df(u), boxed_copy.df(u));
+        assert_eq!(plain.f(u), plain_copy.f(u));
+        assert_eq!(plain.df(u), plain_copy.df(u));
+    }
+    assert_eq!(format!("{:?}", boxed_copy), "Advection { a: -0.5 }");
+
+    // equations which are not thread-safe clone as well, counting on their own
+    let counted: Box<dyn Equation<f64>> = Box::new(Counted::default());
+    counted.f(1.);
+    let copy = counted.clone();
+    copy.f(1.);
+    assert_eq!(
+        format!("{:?}", counted),
+        "Counted { calls: Cell { value: 1 } }"
+    );
+    assert_eq!(
+        format!("{:?}", copy),
+        "Counted { calls: Cell { value: 2 } }"
+    );
+}
@@ -0,0 +1,94 @@
+use fdm::base::{Equation, Simluation};
+use fdm::equations::{Advection, InviscidBurger};
+use fdm::io::Checkpoint;
+use fdm::schemes::Upwind;
+use std::f64::consts::PI;
+use std::fs;
+
+#[test]
+fn checkpoints_are_written_every_interval() {
+    let dir = std::env::temp_dir().join(format!("fdm-checkpoints-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    let dx = 1. / 32.;
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    let checkpoint = Checkpoint::new(&dir, 5);
+    let written = sim
+        .run_with_checkpoints(&Upwind, &Advection { a: 1.0 }, 10, &checkpoint)
+        .unwrap();
+
+    assert_eq!(written, vec![checkpoint.path(5), checkpoint.path(10)]);
+    let mut files: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(
+        files,
+        vec!["checkpoint_000005.csv", "checkpoint_000010.csv"]
+    );
+
+    // the last checkpoint restores the final state and its time
+    let mut restored = Simluation::new(dx, 0.5 * dx, [-1., 1.], |_| 0.);
+    restored.load_csv(checkpoint.path(10)).unwrap();
+    assert!(restored.approx_eq(&sim, 1e-15));
+    assert_eq!(sim.time(), 10. * 0.5 * dx);
+    assert_eq!(restored.time(), sim.time());
+
+    // a resumed run continues the clock
+    restored
+        .run_with_checkpoints(&Upwind, &Advection { a: 1.0 }, 5, &checkpoint)
+        .unwrap();
+    assert_eq!(restored.time(), 15. * 0.5 * dx);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn table_has_one_row_per_cell_with_diagnostics() {
+    let dx = 0.1;
+    let sim = Simluation::new(dx, 0.05, [-1., 1.], |x: f64| x);
+    let table = sim.table(&InviscidBurger);
+
+    assert_eq!(table.len(), sim.len());
+    for (j, row) in table.iter().enumerate() {
+        assert_eq!(row.x, sim.grid[j]);
+        assert_eq!(row.u, sim.state[j]);
+        assert_eq!(row.f, InviscidBurger.f(sim.state[j]));
+        assert_eq!(row.df, sim.state[j]);
+        assert_eq!(row.cfl, sim.state[j].abs() * 0.5);
+    }
+
+    let path = std::env::temp_dir().join(format!("fdm-table-{}.csv", std::process::id()));
+    sim.save_table_csv(&path, &InviscidBurger).unwrap();
+    let text = fs::read_to_string(&path).unwrap();
+    assert_eq!(text.lines().next(), Some("x,u,f,df,cfl"));
+    assert_eq!(text.lines().count(), sim.len() + 1);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn gnuplot_data_parses_back_to_the_state() {
+    let sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| (PI * x).sin());
+    let mut data = vec![];
+    sim.to_gnuplot_data(&mut data).unwrap();
+    sim.to_gnuplot_data(&mut data).unwrap();
+    let text = String::from_utf8(data).unwrap();
+
+    let blocks: Vec<&str> = text.split("\n\n").filter(|b| !b.is_empty()).collect();
+    assert_eq!(blocks.len(), 2);
+    for block in blocks {
+        let rows: Vec<Vec<f64>> = block
+            .lines()
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|v| v.parse().unwrap())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(rows.len(), sim.len());
+        for (row, (&x, &u)) in rows.iter().zip(sim.grid.iter().zip(sim.state.iter())) {
+            assert_eq!(row, &vec![x, u]);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use fdm::limiters::{Limiter, Minmod, MonotonizedCentral, Superbee, VanLeer};
+
+fn limiters() -> [&'static dyn Limiter<f64>; 4] {
+    [&Minmod, &Superbee, &VanLeer, &MonotonizedCentral]
+}
+
+// r over several decades on both sides of zero
+fn ratios() -> impl Iterator<Item = f64> {
+    (-400..=400).map(|i: i32| {
+        let r = 10f64.powf(i.abs() as f64 / 100.) - 1.;
+        if i < 0 {
+            -r
+        } else {
+            r
+        }
+    })
+}
+
+#[test]
+fn limiters_stay_in_the_tvd_region() {
+    for limiter in limiters().iter() {
+        for r in ratios() {
+            let phi = limiter.phi(r);
+            if r <= 0. {
+                assert_eq!(phi, 0., "{:?} at r = {}", limiter, r);
+            } else {
+                assert!(phi >= 0., "{:?} at r = {}", limiter, r);
+                assert!(
+                    phi <= 2f64.min(2. * r) + 1e-15,
+                    "{:?} at r = {}",
+                    limiter,
+                    r
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn limiters_are_second_order_at_smooth_data() {
+    for limiter in limiters().iter() {
+        assert_eq!(limiter.phi(1.), 1., "{:?}", limiter);
+    }
+}
+
+#[test]
+fn limiters_span_the_tvd_region_from_minmod_to_superbee() {
+    for r in ratios().filter(|&r| r > 0.) {
+        let minmod = Minmod.phi(r);
+        let superbee = Superbee.phi(r);
+        for limiter in [&VanLeer as &dyn Limiter<f64>, &MonotonizedCentral].iter() {
+            let phi = limiter.phi(r);
+            assert!(
+                minmod <= phi && phi <= superbee,
+                "{:?} at r = {}",
+                limiter,
+                r
+            );
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use fdm::base::Simluation;
+use fdm::equations::Advection;
+use fdm::numeric::Numeric;
+use fdm::schemes::{Scheme, Upwind};
+use num_traits::{NumCast, ToPrimitive};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A plain wrapper, standing in for e.g. a fixed-point or interval type
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+struct Wrapped(f64);
+
+impl Add for Wrapped {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Wrapped(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Wrapped {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Wrapped(self.0 - rhs.0)
+    }
+}
+
+impl Mul for Wrapped {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Wrapped(self.0 * rhs.0)
+    }
+}
+
+impl Div for Wrapped {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        Wrapped(self.0 / rhs.0)
+    }
+}
+
+impl Neg for Wrapped {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Wrapped(-self.0)
+    }
+}
+
+impl ToPrimitive for Wrapped {
+    fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+    fn to_u64(&self) -> Option<u64> {
+        self.0.to_u64()
+    }
+    fn to_f64(&self) -> Option<f64> {
+        Some(self.0)
+    }
+}
+
+impl NumCast for Wrapped {
+    fn from<N: ToPrimitive>(n: N) -> Option<Self> {
+        n.to_f64().map(Wrapped)
+    }
+}
+
+impl Numeric for Wrapped {
+    fn abs(self) -> Self {
+        Wrapped(self.0.abs())
+    }
+}
+
+#[test]
+fn upwind_runs_on_custom_numeric_type() {
+    let init = |x: f64| (-x * x * 10.).exp();
+    let dx = 1. / 32.;
+    let dt = 0.5 * dx;
+
+    let mut expected = Simluation::new(dx, dt, [-1., 1.], init);
+    let mut wrapped = Simluation::new(Wrapped(dx), Wrapped(dt), [Wrapped(-1.), Wrapped(1.)], |x| {
+        Wrapped(init(x.0))
+    });
+    assert_eq!(wrapped.len(), expected.len());
+
+    for _ in 0..20 {
+        expected.set_state(Upwind.run(&expected, &Advection { a: 1.0 }).unwrap());
+        wrapped.set_state(
+            Upwind
+                .run(&wrapped, &Advection { a: Wrapped(1.0) })
+                .unwrap(),
+        );
+    }
+
+    for (w, e) in wrapped.state.iter().zip(expected.state.iter()) {
+        assert_eq!(w.0, *e);
+    }
+}
+
+#[test]
+fn f64_views_borrow_for_f64_and_convert_f32() {
+    let sim = Simluation::new(0.25, 0.125, [-1., 1.], |x: f64| 2. * x);
+    let grid = sim.grid_view_f64();
+    assert!(grid.is_view());
+    assert_eq!(grid, sim.grid);
+    assert_eq!(sim.state_view_f64(), sim.state);
+
+    let sim = Simluation::new(0.25f32, 0.125, [-1., 1.], |x: f32| 2. * x);
+    let grid = sim.grid_view_f64();
+    assert!(grid.is_owned());
+    for (&a, &b) in grid.iter().zip(sim.grid.iter()) {
+        assert_eq!(a, b as f64);
+    }
+    assert_eq!(sim.state_view_f64()[0], -2.);
+}
+
+#[test]
+fn plot_is_available_for_custom_numeric_types() {
+    let _plot: fn(&Simluation<Wrapped>, &str) = Simluation::<Wrapped>::plot;
+}
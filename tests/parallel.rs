@@ -0,0 +1,34 @@
+#![cfg(feature = "parallel")]
+
+use fdm::base::Simluation;
+use fdm::equations::{BuckleyLeverett, InviscidBurger};
+use fdm::schemes::{LaxWendroff, Scheme, Upwind};
+use rayon::ThreadPoolBuilder;
+use std::f64::consts::PI;
+
+#[test]
+fn parallel_fluxes_are_bit_identical_to_a_single_thread() {
+    // wide enough for several rayon tasks
+    let dx = 1. / 8192.;
+    let sim = Simluation::new(dx, 0.4 * dx, [-1., 1.], |x: f64| 0.5 + 0.4 * (PI * x).sin());
+    let serial = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+    let buckley = BuckleyLeverett {
+        mobility_ratio: 0.5,
+    };
+
+    let schemes: [&(dyn Scheme<f64> + Sync); 2] = [&Upwind, &LaxWendroff];
+    for &scheme in schemes.iter() {
+        let burger = serial.install(|| scheme.run(&sim, &InviscidBurger).unwrap());
+        assert_eq!(
+            burger,
+            pool.install(|| scheme.run(&sim, &InviscidBurger).unwrap())
+        );
+
+        let buckley_serial = serial.install(|| scheme.run(&sim, &buckley).unwrap());
+        assert_eq!(
+            buckley_serial,
+            pool.install(|| scheme.run(&sim, &buckley).unwrap())
+        );
+    }
+}
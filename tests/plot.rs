@@ -0,0 +1,44 @@
+use fdm::plot::{Animation, Plotter};
+
+#[derive(Default)]
+struct MockPlotter {
+    terminals: Vec<(String, String)>,
+}
+
+impl Plotter for MockPlotter {
+    fn set_terminal(&mut self, terminal: &str, output_file: &str) {
+        self.terminals.push((terminal.into(), output_file.into()));
+    }
+}
+
+#[test]
+fn default_animation_keeps_previous_terminal() {
+    let mut plotter = MockPlotter::default();
+    Animation::default().apply(&mut plotter, "out.gif");
+    assert_eq!(
+        plotter.terminals,
+        vec![(
+            "gif animate optimize delay 2 size 480,360".into(),
+            "out.gif".into()
+        )]
+    );
+}
+
+#[test]
+fn configured_animation_is_passed_to_terminal() {
+    let mut plotter = MockPlotter::default();
+    let animation = Animation {
+        delay: 10,
+        width: 1280,
+        height: 720,
+        optimize: false,
+    };
+    animation.apply(&mut plotter, "slow.gif");
+    assert_eq!(
+        plotter.terminals,
+        vec![(
+            "gif animate delay 10 size 1280,720".into(),
+            "slow.gif".into()
+        )]
+    );
+}
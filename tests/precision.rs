@@ -0,0 +1,43 @@
+use fdm::base::Simluation;
+use fdm::equations::Advection;
+use fdm::schemes::{LaxWendroff, Scheme, Upwind};
+use std::f64::consts::PI;
+
+fn advect<T>(scheme: &dyn Scheme<T>, dx: T, steps: usize) -> Simluation<T>
+where
+    T: num_traits::Float + std::fmt::Debug + Send + Sync,
+{
+    let half = T::from(0.5).unwrap();
+    let pi = T::from(PI).unwrap();
+    let one = T::from(1).unwrap();
+    let mut sim = Simluation::new(dx, half * dx, [-one, one], |x: T| (pi * x).sin());
+    for _ in 0..steps {
+        sim.step(scheme, &Advection { a: one }).unwrap();
+    }
+    sim
+}
+
+#[test]
+fn single_precision_follows_double_precision() {
+    let steps = 128;
+    let schemes: [(&dyn Scheme<f32>, &dyn Scheme<f64>); 2] =
+        [(&Upwind, &Upwind), (&LaxWendroff, &LaxWendroff)];
+    for &(s32, s64) in schemes.iter() {
+        let name = s64.describe().name;
+        let single = advect(s32, 1. / 64., steps);
+        let double = advect(s64, 1. / 64., steps);
+        assert_eq!(single.len(), double.len());
+
+        // one period around the domain takes 256 steps, half of it here
+        let max = single.state.iter().fold(0f32, |m, u| m.max(u.abs()));
+        assert!(max <= 1. && max > 0.9, "{}: {}", name, max);
+
+        let error = single
+            .state
+            .iter()
+            .zip(double.state.iter())
+            .fold(0f64, |m, (&s, &d)| m.max((s as f64 - d).abs()));
+        assert!(error < 1e-5, "{}: {}", name, error);
+        assert!((single.time() as f64 - double.time()).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,117 @@
+//! Regression suite comparing every built-in scheme against golden outputs.
+//!
+//! Each scheme is run on a small set of canonical problems for a fixed number
+//! of steps, and the final state is compared against the reference arrays in
+//! `tests/golden`. After an intentional numerical change, regenerate them with
+//!
+//! ```bash
+//! FDM_BLESS=1 cargo test --test regression
+//! ```
+
+use fdm::base::{Equation, Simluation};
+use fdm::equations::{Advection, InviscidBurger};
+use fdm::schemes::{BeamWarming, LaxFriedrichs, LaxWendroff, Scheme, Upwind};
+use ndarray::Array1;
+use std::f64::consts::PI;
+use std::fs;
+use std::path::PathBuf;
+
+const TOLERANCE: f64 = 1e-12;
+const STEPS: usize = 40;
+
+struct Problem {
+    name: &'static str,
+    equation: Box<dyn Equation<f64>>,
+    init: fn(f64) -> f64,
+}
+
+fn problems() -> Vec<Problem> {
+    vec![
+        Problem {
+            name: "AdvectionSine",
+            equation: Box::new(Advection { a: 1.0 }),
+            init: |x| (PI * x).sin(),
+        },
+        Problem {
+            name: "AdvectionSquare",
+            equation: Box::new(Advection { a: 1.0 }),
+            init: |x| if (-0.25..=0.25).contains(&x) { 1.0 } else { 0. },
+        },
+        Problem {
+            name: "BurgerRiemann",
+            equation: Box::new(InviscidBurger),
+            init: |x| if x < 0. { 1.0 } else { 0. },
+        },
+    ]
+}
+
+fn schemes() -> Vec<(&'static str, Box<dyn Scheme<f64>>)> {
+    vec![
+        ("Upwind", Box::new(Upwind)),
+        ("BeamWarming", Box::new(BeamWarming)),
+        ("LaxWendroff", Box::new(LaxWendroff)),
+        ("LaxFriedrichs", Box::new(LaxFriedrichs)),
+    ]
+}
+
+fn solve(problem: &Problem, scheme: &dyn Scheme<f64>) -> Array1<f64> {
+    let dx = 1. / 32.;
+    let dt = 0.6 * dx;
+    let mut sim = Simluation::new(dx, dt, [-1., 1.], problem.init);
+    for _ in 0..STEPS {
+        sim.set_state(scheme.run(&sim, &*problem.equation).unwrap());
+    }
+    sim.state
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{}.txt", name))
+}
+
+fn write_golden(name: &str, state: &Array1<f64>) {
+    let path = golden_path(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let text: String = state.iter().map(|x| format!("{:e}\n", x)).collect();
+    fs::write(path, text).unwrap();
+}
+
+fn read_golden(name: &str) -> Array1<f64> {
+    let path = golden_path(name);
+    let text = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Missing golden file {:?}, run with FDM_BLESS=1", path));
+    text.lines().map(|line| line.parse().unwrap()).collect()
+}
+
+#[test]
+fn schemes_match_golden_outputs() {
+    let bless = std::env::var_os("FDM_BLESS").is_some();
+    let mut mismatches = vec![];
+
+    for problem in problems().iter() {
+        for (scheme_name, scheme) in schemes().iter() {
+            let name = format!("{}-{}", problem.name, scheme_name);
+            let state = solve(problem, &**scheme);
+
+            if bless {
+                write_golden(&name, &state);
+                continue;
+            }
+
+            let golden = read_golden(&name);
+            assert_eq!(golden.len(), state.len(), "{}: length changed", name);
+            let max_diff = state
+                .iter()
+                .zip(golden.iter())
+                .map(|(a, b)| (a - b).abs())
+                .fold(0., f64::max);
+            if max_diff > TOLERANCE {
+                mismatches.push(format!("{}: max difference {:e}", name, max_diff));
+            }
+        }
+    }
+
+    assert!(mismatches.is_empty(), "{:#?}", mismatches);
+}
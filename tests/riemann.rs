@@ -0,0 +1,64 @@
+use fdm::base::Simluation;
+use fdm::boundary::Boundary;
+use fdm::equations::InviscidBurger;
+use fdm::riemann::riemann_exact;
+use fdm::schemes::{Roe, Scheme};
+
+#[test]
+fn burgers_shock_moves_at_rankine_hugoniot_speed() {
+    // s = (f(2) - f(1)) / (2 - 1) = 3/2
+    for &(xi, u) in [(-1., 2.), (1.49, 2.), (1.51, 1.), (3., 1.)].iter() {
+        assert_eq!(riemann_exact(&InviscidBurger, 2., 1., xi), u);
+    }
+}
+
+#[test]
+fn burgers_rarefaction_fan_is_linear_in_xi() {
+    assert_eq!(riemann_exact(&InviscidBurger, 0., 1., -0.5), 0.);
+    assert_eq!(riemann_exact(&InviscidBurger, 0., 1., 1.5), 1.);
+    for &xi in [0.1, 0.25, 0.5, 0.9].iter() {
+        let u: f64 = riemann_exact(&InviscidBurger, 0., 1., xi);
+        assert!((u - xi).abs() < 1e-12, "u = {} at xi = {}", u, xi);
+    }
+}
+
+// Roe with the entropy fix is Godunov's scheme away from sonic points
+fn collapse_godunov(u_left: f64, u_right: f64, steps: &[usize]) -> Vec<f64> {
+    let dx = 1. / 64.;
+    let dt = 0.25 * dx;
+    let mut sim = Simluation::new(dx, dt, [-1., 1.], |x| if x < 0. { u_left } else { u_right });
+    sim.set_boundary(Boundary::Dirichlet([u_left, u_right]));
+
+    // the jump sits between x = -dx and x = 0
+    let x0 = -dx / 2.;
+    let mut done = 0;
+    steps
+        .iter()
+        .map(|&n| {
+            for _ in done..n {
+                sim.set_state(
+                    Roe { entropy_fix: true }
+                        .run(&sim, &InviscidBurger)
+                        .unwrap(),
+                );
+            }
+            done = n;
+            let t = n as f64 * dt;
+            sim.self_similar_collapse(&InviscidBurger, u_left, u_right, x0, t)
+                .l1
+        })
+        .collect()
+}
+
+#[test]
+fn godunov_burgers_collapses_onto_self_similar_profile() {
+    for &(u_left, u_right) in [(1.5, 0.5), (0.5, 1.5)].iter() {
+        let errors = collapse_godunov(u_left, u_right, &[40, 80, 160]);
+        assert!(errors[2] < 0.1, "errors = {:?}", errors);
+        assert!(
+            errors.windows(2).all(|pair| pair[1] < pair[0]),
+            "errors = {:?}",
+            errors
+        );
+    }
+}
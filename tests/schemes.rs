@@ -0,0 +1,883 @@
+use fdm::base::{Equation, Simluation};
+use fdm::boundary::{Boundary, GhostFill};
+use fdm::equations::{Advection, BuckleyLeverett, InviscidBurger, ViscousBurger};
+use fdm::error::SchemeError;
+use fdm::integrators::{ForwardEuler, SspRk2, SspRk3, TimeIntegrator};
+use fdm::limiters::Minmod;
+use fdm::schemes::{
+    recommend_scheme, BeamWarming, Blend, Extended, Fct, Godunov, LaxFriedrichs, LaxWendroff,
+    MacCormack, Muscl, OsherSolomon, Richtmyer, Roe, Rusanov, Scheme, SecondOrderUpwind,
+    SingleStepLaxWendroff, Upwind, UpwindBiased3,
+};
+use fdm::BoxedScheme;
+use ndarray::Array1;
+use std::cell::Cell;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+fn max_jump(sim: &Simluation<f64>) -> f64 {
+    sim.state
+        .iter()
+        .zip(sim.state.iter().skip(1))
+        .map(|(l, r)| (r - l).abs())
+        .fold(0., f64::max)
+}
+
+fn transonic_rarefaction() -> Simluation<f64> {
+    let dx = 1. / 32.;
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x| if x < 0. { -1. } else { 1. });
+    sim.set_boundary(Boundary::Dirichlet([-1., 1.]));
+    sim
+}
+
+#[test]
+fn roe_entropy_fix_removes_expansion_shock() {
+    let mut plain = transonic_rarefaction();
+    let mut fixed = transonic_rarefaction();
+    for _ in 0..20 {
+        plain.set_state(
+            Roe { entropy_fix: false }
+                .run(&plain, &InviscidBurger)
+                .unwrap(),
+        );
+        fixed.set_state(
+            Roe { entropy_fix: true }
+                .run(&fixed, &InviscidBurger)
+                .unwrap(),
+        );
+    }
+
+    // without the fix the stationary expansion shock persists
+    assert!((max_jump(&plain) - 2.).abs() < 1e-12);
+    assert!(max_jump(&fixed) < 1.);
+}
+
+#[test]
+fn fct_introduces_no_new_extrema() {
+    let dx = 1. / 32.;
+    let square = |x: f64| if (-0.25..=0.25).contains(&x) { 1. } else { 0. };
+    let eq = Advection { a: 1.0 };
+    let fct = Fct {
+        low: Upwind,
+        high: LaxWendroff,
+    };
+
+    let mut limited = Simluation::new(dx, 0.5 * dx, [-1., 1.], square);
+    let mut plain = Simluation::new(dx, 0.5 * dx, [-1., 1.], square);
+    for _ in 0..40 {
+        limited.set_state(fct.run(&limited, &eq).unwrap());
+        plain.set_state(LaxWendroff.run(&plain, &eq).unwrap());
+    }
+
+    let bounds = |sim: &Simluation<f64>| {
+        let min = sim.state.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = sim.state.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        (min, max)
+    };
+    let (min, max) = bounds(&limited);
+    assert!(min >= -1e-12 && max <= 1. + 1e-12);
+
+    // Lax-Wendroff alone overshoots
+    let (min, max) = bounds(&plain);
+    assert!(min < -1e-3 || max > 1. + 1e-3);
+}
+
+#[test]
+fn beam_warming_reduces_to_upwind_next_to_dirichlet_boundaries() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (3. * x).sin());
+    let n = sim.len();
+
+    // periodic runs keep the full stencil everywhere
+    let [full_neg, full_pos] = BeamWarming.flux(&sim, &eq).unwrap();
+    let [neg, pos] = BeamWarming.mixed_flux(&sim, &eq).unwrap();
+    assert_eq!(neg, full_neg);
+    assert_eq!(pos, full_pos);
+
+    sim.set_boundary(Boundary::Dirichlet([0., 0.]));
+    let [full_neg, full_pos] = BeamWarming.flux(&sim, &eq).unwrap();
+    let [edge_neg, edge_pos] = Upwind.flux(&sim, &eq).unwrap();
+    let [neg, pos] = BeamWarming.mixed_flux(&sim, &eq).unwrap();
+
+    // interfaces 0, 1 and n - 1, n use the reduced stencil
+    for j in [0, 1, n - 1].iter().cloned() {
+        assert_eq!(neg[j], edge_neg[j]);
+    }
+    for j in [0, n - 2, n - 1].iter().cloned() {
+        assert_eq!(pos[j], edge_pos[j]);
+    }
+    for j in 2..n - 1 {
+        assert_eq!(neg[j], full_neg[j]);
+        assert_eq!(pos[j - 1], full_pos[j - 1]);
+    }
+    assert_ne!(neg[1], full_neg[1]);
+}
+
+#[test]
+fn extended_state_serves_narrower_stencils_as_views() {
+    let dx = 1. / 32.;
+    let eq = BuckleyLeverett {
+        mobility_ratio: 0.5,
+    };
+    let mut sim = Simluation::new(dx, 0.25 * dx, [-1., 1.], |x: f64| {
+        0.5 + 0.4 * (PI * x).sin()
+    });
+    sim.set_boundary(Boundary::Dirichlet([0.9, 0.1]));
+    let extended = Extended::new(&sim, &eq, 3);
+    for ext in 0..=3 {
+        let (u, f) = (extended.u(ext), extended.f(ext));
+        assert!(u.is_view() && f.is_view());
+        assert_eq!(u, sim.get_u(ext));
+        assert_eq!(f, sim.get_f(&eq, ext));
+    }
+
+    let schemes: [&dyn Scheme<f64>; 3] = [
+        &SecondOrderUpwind { entropy_fix: true },
+        &BeamWarming,
+        &Godunov,
+    ];
+    for &scheme in schemes.iter() {
+        assert_eq!(scheme.flux_with(&extended), scheme.flux(&sim, &eq));
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "ghost cells requested")]
+fn extended_state_rejects_wider_requests_in_debug_builds() {
+    let sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| x);
+    let extended = Extended::new(&sim, &InviscidBurger, 1);
+    let _ = BeamWarming.flux_with(&extended);
+}
+
+#[test]
+fn single_step_lax_wendroff_only_agrees_on_linear_flux() {
+    let dx = 1. / 32.;
+    let init = |x: f64| 0.5 + 0.25 * (PI * x).sin();
+    let compare = |eq: &dyn Equation<f64>| {
+        let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], init);
+        let mut divergence: f64 = 0.;
+        for _ in 0..20 {
+            divergence =
+                divergence.max(sim.scheme_divergence(&LaxWendroff, &SingleStepLaxWendroff, eq));
+            sim.set_state(LaxWendroff.run(&sim, eq).unwrap());
+        }
+        divergence
+    };
+
+    assert!(compare(&Advection { a: 1.0 }) < 1e-14);
+    assert!(compare(&InviscidBurger) > 1e-6);
+}
+
+#[test]
+fn mac_cormack_reduces_to_lax_wendroff_on_linear_advection() {
+    let dx = 1. / 32.;
+    let init = |x: f64| if x.abs() < 0.5 { 1. } else { 0. };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], init);
+    for eq in [&Advection { a: 1.0 }, &Advection { a: -0.7 }].iter() {
+        for _ in 0..20 {
+            assert!(sim.scheme_divergence(&LaxWendroff, &MacCormack, *eq) < 1e-14);
+            sim.step(&MacCormack, *eq).unwrap();
+        }
+    }
+    assert!(sim.scheme_divergence(&LaxWendroff, &MacCormack, &InviscidBurger) > 1e-6);
+}
+
+#[test]
+fn run_into_reuses_the_buffer_and_matches_run() {
+    let dx = 1. / 32.;
+    let mut sim = Simluation::new(dx, 0.4 * dx, [-1., 1.], |x: f64| 0.5 * (PI * x).sin());
+    sim.set_boundary(Boundary::Dirichlet([0.2, -0.1]));
+    let schemes: [&dyn Scheme<f64>; 4] = [&Upwind, &LaxWendroff, &BeamWarming, &Rusanov];
+    let eq = ViscousBurger { nu: 0.01 };
+
+    // starts with the wrong length, reallocated once
+    let mut out = Array1::zeros(3);
+    for &scheme in schemes.iter() {
+        scheme.run_into(&sim, &eq, &mut out).unwrap();
+        let buffer = out.as_ptr();
+        assert_eq!(out, scheme.run(&sim, &eq).unwrap(), "{:?}", scheme);
+        scheme.run_into(&sim, &eq, &mut out).unwrap();
+        assert_eq!(out.as_ptr(), buffer);
+    }
+
+    // the stepped states are the same as well
+    let mut stepped = sim.clone();
+    for _ in 0..10 {
+        let state = LaxWendroff.run(&sim, &eq).unwrap();
+        sim.set_state(state);
+        stepped.step(&LaxWendroff, &eq).unwrap();
+    }
+    assert_eq!(stepped.state, sim.state);
+
+    // and match the forward-Euler update written out by hand
+    let expected = &sim.state + &Upwind.residual(&sim, &eq).unwrap();
+    assert_eq!(Upwind.run(&sim, &eq).unwrap(), expected);
+}
+
+// a custom update, halving the state whatever the integrator
+#[derive(Debug, Clone)]
+struct Halving;
+
+impl Scheme<f64> for Halving {
+    fn flux_with(&self, extended: &Extended<f64>) -> Result<[Array1<f64>; 2], SchemeError> {
+        Upwind.flux_with(extended)
+    }
+
+    fn run_with_into(
+        &self,
+        sim: &Simluation<f64>,
+        _eq: &dyn Equation<f64>,
+        _integrator: &dyn TimeIntegrator<f64>,
+        out: &mut Array1<f64>,
+    ) -> Result<(), SchemeError> {
+        *out = sim.state.mapv(|u| u / 2.);
+        Ok(())
+    }
+}
+
+#[test]
+fn every_run_method_and_step_share_one_update() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    let halved = sim.state.mapv(|u| u / 2.);
+
+    assert_eq!(Halving.run(&sim, &eq).unwrap(), halved);
+    assert_eq!(Halving.run_with(&sim, &eq, &SspRk3).unwrap(), halved);
+    let mut out = Array1::zeros(0);
+    Halving.run_into(&sim, &eq, &mut out).unwrap();
+    assert_eq!(out, halved);
+    sim.step(&Halving, &eq).unwrap();
+    assert_eq!(sim.state, halved);
+}
+
+// one SSP-RK3 step assembled from `rk_substep`
+fn ssp_rk3(sim: &mut Simluation<f64>, scheme: &dyn Scheme<f64>, eq: &dyn Equation<f64>) {
+    let c = sim.dt_over_dx();
+    let residual = |sim: &Simluation<f64>| {
+        let [h_neg, h_pos] = scheme.flux(sim, eq).unwrap();
+        h_neg - h_pos
+    };
+
+    let u0 = sim.state.clone();
+    let u1 = sim.rk_substep(&residual(sim), c);
+    sim.set_state(u1);
+    let u2 = sim.rk_substep(&residual(sim), c) * 0.25 + &u0 * 0.75;
+    sim.set_state(u2);
+    let u3 = sim.rk_substep(&residual(sim), c) * (2. / 3.) + &u0 * (1. / 3.);
+    sim.set_state(u3);
+}
+
+#[test]
+fn upwind_biased_third_order_converges_on_smooth_advection() {
+    let errors: Vec<f64> = [32, 64, 128]
+        .iter()
+        .map(|&n| {
+            let dx = 2. / n as f64;
+            let dt = 0.4 * dx;
+            let mut sim = Simluation::new(dx, dt, [-1., 1.], |x: f64| (PI * x).sin());
+            let steps = (0.5 / dt).round() as usize;
+            let eq = Advection { a: 1.0 };
+            for _ in 0..steps {
+                sim.set_state(UpwindBiased3.run_with(&sim, &eq, &SspRk3).unwrap());
+            }
+            let t = steps as f64 * dt;
+            sim.grid
+                .iter()
+                .zip(sim.state.iter())
+                .map(|(x, u)| (u - (PI * (x - t)).sin()).abs())
+                .fold(0., f64::max)
+        })
+        .collect();
+
+    for pair in errors.windows(2) {
+        let order = (pair[0] / pair[1]).log2();
+        assert!(order > 2.8, "order = {}, errors = {:?}", order, errors);
+    }
+}
+
+#[test]
+fn built_in_schemes_describe_themselves() {
+    let describe = |scheme: &dyn Scheme<f64>| {
+        let info = scheme.describe();
+        assert!(info.is_conservative);
+        (info.name, info.order, info.required_ext, info.cfl_limit)
+    };
+
+    assert_eq!(describe(&Upwind), ("Upwind".into(), 1, 1, 1.));
+    assert_eq!(describe(&BeamWarming), ("BeamWarming".into(), 2, 2, 1.));
+    assert_eq!(describe(&LaxWendroff), ("LaxWendroff".into(), 2, 1, 1.));
+    assert_eq!(describe(&Richtmyer), ("Richtmyer".into(), 2, 1, 1.));
+    assert_eq!(
+        describe(&SingleStepLaxWendroff),
+        ("SingleStepLaxWendroff".into(), 2, 1, 1.)
+    );
+    assert_eq!(describe(&UpwindBiased3), ("UpwindBiased3".into(), 3, 2, 0.));
+    assert_eq!(describe(&LaxFriedrichs), ("LaxFriedrichs".into(), 1, 1, 1.));
+    assert_eq!(describe(&Rusanov), ("Rusanov".into(), 1, 1, 1.));
+    assert_eq!(describe(&Godunov), ("Godunov".into(), 1, 1, 1.));
+    assert_eq!(describe(&MacCormack), ("MacCormack".into(), 2, 1, 1.));
+    assert_eq!(
+        describe(&Roe { entropy_fix: true }),
+        ("Roe".into(), 1, 1, 1.)
+    );
+    assert_eq!(
+        describe(&Fct {
+            low: Upwind,
+            high: BeamWarming
+        }),
+        ("Fct(Upwind, BeamWarming)".into(), 2, 2, 1.)
+    );
+    assert_eq!(
+        describe(&SecondOrderUpwind { entropy_fix: false }),
+        ("SecondOrderUpwind".into(), 2, 3, 1.)
+    );
+    assert_eq!(
+        describe(&Muscl { limiter: Minmod }),
+        ("Muscl(Minmod)".into(), 2, 2, 0.5)
+    );
+
+    // the stencil near boundaries and the restriction to linear flux
+    let edges = |scheme: &dyn Scheme<f64>| {
+        let info = scheme.describe();
+        (info.edge_width, info.requires_linear_flux)
+    };
+    assert_eq!(edges(&Upwind), (0, false));
+    assert_eq!(edges(&BeamWarming), (2, true));
+    assert_eq!(edges(&SingleStepLaxWendroff), (0, true));
+    assert_eq!(edges(&UpwindBiased3), (2, false));
+    assert_eq!(edges(&Muscl { limiter: Minmod }), (1, false));
+    assert_eq!(
+        edges(&Fct {
+            low: Upwind,
+            high: BeamWarming
+        }),
+        (0, true)
+    );
+}
+
+#[test]
+fn stated_stencils_match_the_measured_ones() {
+    let fct = Fct {
+        low: Upwind,
+        high: SecondOrderUpwind { entropy_fix: true },
+    };
+    let blend = Blend::new(Box::new(Upwind), Box::new(UpwindBiased3), 0.5);
+    let schemes: [&dyn Scheme<f64>; 18] = [
+        &Upwind,
+        &BeamWarming,
+        &SecondOrderUpwind { entropy_fix: false },
+        &LaxWendroff,
+        &Richtmyer,
+        &SingleStepLaxWendroff,
+        &MacCormack,
+        &UpwindBiased3,
+        &LaxFriedrichs,
+        &Rusanov,
+        &Roe { entropy_fix: true },
+        &Godunov,
+        &Muscl { limiter: Minmod },
+        &OsherSolomon { segments: 4 },
+        &fct,
+        &blend,
+        &Central,
+        &Halving,
+    ];
+    for &scheme in schemes.iter() {
+        let info = scheme.describe();
+        assert_eq!(scheme.required_ext(), info.required_ext, "{}", info.name);
+        assert_eq!(scheme.edge_width(), info.edge_width, "{}", info.name);
+    }
+}
+
+#[test]
+fn richtmyer_steps_like_lax_wendroff() {
+    let dx = 1. / 32.;
+    let sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    assert_eq!(
+        Richtmyer.run(&sim, &InviscidBurger).unwrap(),
+        LaxWendroff.run(&sim, &InviscidBurger).unwrap()
+    );
+}
+
+// a custom scheme relying on the default `describe`
+#[derive(Debug, Clone)]
+struct Central;
+
+impl Scheme<f64> for Central {
+    fn flux_with(&self, extended: &Extended<f64>) -> Result<[Array1<f64>; 2], SchemeError> {
+        let n = extended.sim.len();
+        let f = extended.f(1);
+        let h_neg = Array1::from_shape_fn(n, |j| (f[j] + f[j + 1]) / 2.);
+        let h_pos = Array1::from_shape_fn(n, |j| (f[j + 1] + f[j + 2]) / 2.);
+        Ok([h_neg, h_pos])
+    }
+}
+
+#[test]
+fn default_description_measures_the_stencil() {
+    let info = Central.describe();
+    assert_eq!(info.name, "Central");
+    assert_eq!((info.order, info.required_ext, info.cfl_limit), (1, 1, 1.));
+    assert_eq!((info.edge_width, info.requires_linear_flux), (0, false));
+    assert!(info.is_conservative);
+}
+
+#[test]
+fn second_order_upwind_converges_on_smooth_advection() {
+    let errors: Vec<f64> = [32, 64, 128]
+        .iter()
+        .map(|&n| {
+            let dx = 2. / n as f64;
+            let dt = 0.5 * dx;
+            let mut sim = Simluation::new(dx, dt, [-1., 1.], |x: f64| (PI * x).sin());
+            let steps = (0.5 / dt).round() as usize;
+            let scheme = SecondOrderUpwind { entropy_fix: true };
+            for _ in 0..steps {
+                sim.set_state(scheme.run(&sim, &Advection { a: 1.0 }).unwrap());
+            }
+            let t = steps as f64 * dt;
+            sim.error_norms(&|x| (PI * (x - t)).sin()).linf
+        })
+        .collect();
+
+    for pair in errors.windows(2) {
+        let order = (pair[0] / pair[1]).log2();
+        assert!(order > 1.8, "order = {}, errors = {:?}", order, errors);
+    }
+}
+
+#[test]
+fn second_order_upwind_entropy_fix_opens_transonic_rarefaction() {
+    let mut plain = transonic_rarefaction();
+    let mut fixed = transonic_rarefaction();
+    for _ in 0..20 {
+        let scheme = SecondOrderUpwind { entropy_fix: false };
+        plain.set_state(scheme.run(&plain, &InviscidBurger).unwrap());
+        let scheme = SecondOrderUpwind { entropy_fix: true };
+        fixed.set_state(scheme.run(&fixed, &InviscidBurger).unwrap());
+    }
+
+    assert!((max_jump(&plain) - 2.).abs() < 1e-12);
+    assert!(max_jump(&fixed) < 0.5, "max jump = {}", max_jump(&fixed));
+
+    // the fan is centered at the initial jump between x = -dx and x = 0
+    let dx = 1. / 32.;
+    let t = 20. * 0.5 * dx;
+    let exact = |x: f64| ((x + dx / 2.) / t).clamp(-1., 1.);
+    assert!(
+        fixed.error_norms(&exact).l1 < 0.05,
+        "{:?}",
+        fixed.error_norms(&exact)
+    );
+}
+
+#[test]
+fn forward_euler_reproduces_the_explicit_update_bit_for_bit() {
+    let dx = 1. / 32.;
+    let schemes: Vec<Box<dyn Scheme<f64>>> = vec![
+        Box::new(Upwind),
+        Box::new(BeamWarming),
+        Box::new(SecondOrderUpwind { entropy_fix: true }),
+        Box::new(LaxWendroff),
+        Box::new(SingleStepLaxWendroff),
+        Box::new(UpwindBiased3),
+        Box::new(LaxFriedrichs),
+        Box::new(Roe { entropy_fix: true }),
+        Box::new(Fct {
+            low: Upwind,
+            high: LaxWendroff,
+        }),
+    ];
+    for scheme in schemes.iter() {
+        let mut sim = Simluation::new(dx, 0.4 * dx, [-1., 1.], |x: f64| 0.5 + (PI * x).sin());
+        sim.set_boundary(Boundary::Dirichlet([0.5, 0.5]));
+        for _ in 0..10 {
+            let [h_neg, h_pos] = scheme.mixed_flux(&sim, &InviscidBurger).unwrap();
+            let c = sim.dt_over_dx();
+            let expected = &sim.state - &(h_pos - h_neg).mapv(|x| c * x);
+
+            let u = scheme
+                .run_with(&sim, &InviscidBurger, &ForwardEuler)
+                .unwrap();
+            assert_eq!(u, expected, "{:?}", scheme);
+            assert_eq!(scheme.run(&sim, &InviscidBurger).unwrap(), u);
+            sim.set_state(u);
+        }
+    }
+}
+
+#[test]
+fn ssp_rk3_integrator_matches_hand_written_stages() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let mut manual = Simluation::new(dx, 0.4 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    let mut generic = manual.clone();
+    for _ in 0..20 {
+        ssp_rk3(&mut manual, &UpwindBiased3, &eq);
+        generic.set_state(UpwindBiased3.run_with(&generic, &eq, &SspRk3).unwrap());
+    }
+    assert!(generic.approx_eq(&manual, 1e-13));
+}
+
+#[test]
+fn ssp_rk2_averages_two_forward_euler_steps_on_linear_schemes() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let sim = Simluation::new(dx, 0.4 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    let mut twice = sim.clone();
+    twice.step(&Upwind, &eq).unwrap();
+    twice.step(&Upwind, &eq).unwrap();
+    let heun = Upwind.run_with(&sim, &eq, &SspRk2).unwrap();
+    let average = (&sim.state + &twice.state) / 2.;
+    for (a, b) in heun.iter().zip(average.iter()) {
+        assert!((a - b).abs() < 1e-15);
+    }
+}
+
+#[test]
+fn ssp_integrators_reach_their_temporal_order() {
+    // same grid for all runs, so only the time error changes with dt
+    let dx = 1. / 16.;
+    let eq = Advection { a: 1.0 };
+    let solve = |dt: f64, integrator: &dyn TimeIntegrator<f64>| {
+        let mut sim = Simluation::new(dx, dt, [-1., 1.], |x: f64| (PI * x).sin());
+        for _ in 0..(0.5 / dt).round() as usize {
+            sim.set_state(UpwindBiased3.run_with(&sim, &eq, integrator).unwrap());
+        }
+        sim
+    };
+    let reference = solve(dx / 64., &SspRk3);
+    let error = |dt: f64, integrator: &dyn TimeIntegrator<f64>| {
+        let sim = solve(dt, integrator);
+        (&sim.state - &reference.state).mapv(f64::abs).sum()
+    };
+    let ratio = |integrator: &dyn TimeIntegrator<f64>| {
+        error(dx / 4., integrator) / error(dx / 8., integrator)
+    };
+    let (euler, heun, rk3) = (ratio(&ForwardEuler), ratio(&SspRk2), ratio(&SspRk3));
+    assert!(euler > 1.8 && euler < 2.2, "{}", euler);
+    assert!(heun > 3.6 && heun < 4.4, "{}", heun);
+    assert!(rk3 > 7., "{}", rk3);
+}
+
+#[test]
+fn blend_interpolates_between_its_schemes() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    let mut blend = Blend::new(Box::new(Upwind), Box::new(LaxWendroff), 0.);
+
+    assert_eq!(
+        blend.run(&sim, &eq).unwrap(),
+        Upwind.run(&sim, &eq).unwrap()
+    );
+    assert_eq!(blend.describe().order, 1);
+    blend.set_theta(1.);
+    assert_eq!(
+        blend.run(&sim, &eq).unwrap(),
+        LaxWendroff.run(&sim, &eq).unwrap()
+    );
+    assert_eq!(blend.describe().order, 2);
+
+    blend.set_theta(0.5);
+    let [neg, pos] = blend.flux(&sim, &eq).unwrap();
+    let [low_neg, low_pos] = Upwind.flux(&sim, &eq).unwrap();
+    let [high_neg, high_pos] = LaxWendroff.flux(&sim, &eq).unwrap();
+    for j in 0..sim.len() {
+        assert!((neg[j] - (low_neg[j] + high_neg[j]) / 2.).abs() < 1e-15);
+        assert!((pos[j] - (low_pos[j] + high_pos[j]) / 2.).abs() < 1e-15);
+    }
+}
+
+#[test]
+fn recommended_schemes_follow_the_smoothness_of_the_data() {
+    let dx = 1. / 32.;
+    let sine = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    let square = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| {
+        if (-0.25..=0.25).contains(&x) {
+            1.
+        } else {
+            0.
+        }
+    });
+    let advection = Advection { a: 1.0 };
+
+    assert_eq!(recommend_scheme(&sine, &advection), "LaxWendroff");
+    assert_eq!(recommend_scheme(&sine, &InviscidBurger), "LaxWendroff");
+    assert_eq!(recommend_scheme(&square, &advection), "Upwind");
+    assert_eq!(recommend_scheme(&square, &InviscidBurger), "Roe");
+    // f' vanishes at both 0 and 1, but not in between
+    let front = Simluation::with_cells([0., 1.], 5, 0.1, |x: f64| {
+        [0., 0., 0.5, 1., 1.][(x * 5.) as usize]
+    });
+    let buckley_leverett = BuckleyLeverett {
+        mobility_ratio: 0.5,
+    };
+    assert_eq!(recommend_scheme(&front, &buckley_leverett), "Roe");
+
+    // the names are those reported by `describe`
+    assert_eq!(Scheme::<f64>::describe(&LaxWendroff).name, "LaxWendroff");
+    assert_eq!(
+        Scheme::<f64>::describe(&Roe { entropy_fix: true }).name,
+        "Roe"
+    );
+}
+
+#[test]
+fn osher_solomon_converges_on_buckley_leverett_riemann_problem() {
+    let eq = BuckleyLeverett {
+        mobility_ratio: 0.5,
+    };
+    let scheme = OsherSolomon { segments: 16 };
+    let solve = |dx: f64| {
+        let dt = 0.25 * dx;
+        let mut sim = Simluation::new(dx, dt, [-0.5, 1.5], |x| if x < 0. { 1. } else { 0. });
+        sim.set_boundary(Boundary::Dirichlet([1., 0.]));
+        for _ in 0..(0.5 / dt).round() as usize {
+            sim.set_state(scheme.run(&sim, &eq).unwrap());
+        }
+        sim
+    };
+
+    let reference = solve(1. / 512.);
+    let errors: Vec<f64> = [1. / 32., 1. / 64., 1. / 128.]
+        .iter()
+        .map(|&dx| {
+            let sim = solve(dx);
+            // monotone up to the quadrature error of the path integral
+            let bounds = -1e-6..=1. + 1e-6;
+            assert!(sim.state.iter().all(|u| bounds.contains(u)));
+            sim.error_norms(&|x| reference.sample_at(x).unwrap()).l1
+        })
+        .collect();
+
+    assert!(errors[2] < 0.02, "errors = {:?}", errors);
+    assert!(
+        errors.windows(2).all(|pair| pair[1] < pair[0]),
+        "errors = {:?}",
+        errors
+    );
+}
+
+#[test]
+fn rhs_vanishes_on_constants_and_is_uniform_on_ramps() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 2.0 };
+    let constant = Simluation::new(dx, 0.25 * dx, [-1., 1.], |_| 0.7);
+    let mut ramp = Simluation::new(dx, 0.25 * dx, [-1., 1.], |x: f64| 3. * x);
+    ramp.set_ghost_fill(Some(GhostFill::Linear), Some(GhostFill::Linear));
+
+    let schemes: [&dyn Scheme<f64>; 3] = [&Upwind, &LaxWendroff, &Roe { entropy_fix: true }];
+    for scheme in schemes.iter() {
+        assert!(scheme.rhs(&constant, &eq).unwrap().iter().all(|&r| r == 0.));
+        // -a u_x
+        for r in scheme.rhs(&ramp, &eq).unwrap().iter() {
+            assert!((r + 6.).abs() < 1e-12, "{:?}: {}", scheme, r);
+        }
+    }
+}
+
+#[test]
+fn cfl_violations_are_reported_instead_of_panicking() {
+    let dx = 1. / 32.;
+    // only the cells around x = 0.5 move faster than dx / dt
+    let sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| {
+        if (x - 0.5).abs() < 0.01 {
+            5.
+        } else {
+            0.5
+        }
+    });
+
+    match Upwind.run(&sim, &InviscidBurger) {
+        Err(SchemeError::CflViolation { v, location }) => {
+            assert!(v > 1.);
+            assert!((sim.grid[location] - 0.5).abs() <= dx, "at {}", location);
+        }
+        other => panic!("expected a CFL violation, got {:?}", other),
+    }
+    assert!(LaxWendroff.run(&sim, &Advection { a: 1.0 }).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "Check the CFL condition!")]
+fn run_unchecked_panics_on_cfl_violation() {
+    let dx = 1. / 32.;
+    let sim = Simluation::new(dx, 2. * dx, [-1., 1.], |x: f64| x);
+    Upwind.run_unchecked(&sim, &Advection { a: 1.0 });
+}
+
+#[test]
+fn rusanov_keeps_burgers_shock_sharper_than_lax_friedrichs() {
+    let dx = 1. / 64.;
+    let square = |x: f64| if (0. ..=1.).contains(&x) { 1.0 } else { 0. };
+    let mut rusanov = Simluation::new(dx, 0.4 * dx, [-1., 1.], square);
+    let mut lax_friedrichs = rusanov.clone();
+    for _ in 0..60 {
+        rusanov.step(&Rusanov, &InviscidBurger).unwrap();
+        lax_friedrichs
+            .step(&LaxFriedrichs, &InviscidBurger)
+            .unwrap();
+    }
+    assert!(rusanov.conservation_drift().abs() < 1e-12);
+    assert!(
+        max_jump(&rusanov) > 1.5 * max_jump(&lax_friedrichs),
+        "{} vs {}",
+        max_jump(&rusanov),
+        max_jump(&lax_friedrichs)
+    );
+}
+
+#[test]
+fn godunov_moves_burgers_shock_at_rankine_hugoniot_speed() {
+    let dx = 1. / 64.;
+    let dt = 0.4 * dx;
+    let mut sim = Simluation::new(dx, dt, [-1., 1.], |x: f64| if x < -0.5 { 2. } else { 1. });
+    sim.set_boundary(Boundary::Dirichlet([2., 1.]));
+
+    // s = (f(2) - f(1)) / (2 - 1) = 3/2, and the mass between the end states
+    // locates the shock
+    let steps = 40;
+    for _ in 0..steps {
+        sim.step(&Godunov, &InviscidBurger).unwrap();
+    }
+    let t = steps as f64 * dt;
+    let shock = -1. + (sim.mass() - 2.) / (2. - 1.);
+    assert!(
+        (shock - (-0.5 + 1.5 * t)).abs() < dx,
+        "x = {} at t = {}",
+        shock,
+        t
+    );
+    assert!(sim.state.iter().all(|&u| (1. ..=2.).contains(&u)));
+}
+
+#[test]
+fn godunov_opens_transonic_rarefaction_without_entropy_fix() {
+    let mut sim = transonic_rarefaction();
+    for _ in 0..20 {
+        sim.step(&Godunov, &InviscidBurger).unwrap();
+    }
+    assert!(max_jump(&sim) < 1.);
+
+    // the reversed states form a stationary shock, which stays sharp
+    let mut flipped = transonic_rarefaction();
+    flipped.set_state(flipped.state.mapv(|u| -u));
+    flipped.set_boundary(Boundary::Dirichlet([1., -1.]));
+    for _ in 0..20 {
+        flipped.step(&Godunov, &InviscidBurger).unwrap();
+    }
+    assert!(max_jump(&flipped) > 1.);
+}
+
+#[test]
+fn muscl_minmod_is_second_order_without_lax_wendroff_oscillations() {
+    let muscl = Muscl { limiter: Minmod };
+    assert_eq!(
+        Scheme::<f64>::describe(&muscl).name,
+        "Muscl(Minmod)".to_string()
+    );
+    let eq = Advection { a: 1.0 };
+
+    let dx = 1. / 64.;
+    let square = |x: f64| if (0. ..=0.5).contains(&x) { 1.0 } else { 0. };
+    let mut limited = Simluation::new(dx, 0.4 * dx, [-1., 1.], square);
+    let mut lax_wendroff = limited.clone();
+    for _ in 0..100 {
+        limited.set_state(muscl.run_with(&limited, &eq, &SspRk3).unwrap());
+        lax_wendroff.step(&LaxWendroff, &eq).unwrap();
+    }
+    let (lo, hi) = limited
+        .state
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &u| (lo.min(u), hi.max(u)));
+    assert!(lo > -1e-12 && hi < 1. + 1e-12, "{} {}", lo, hi);
+    assert!(lax_wendroff.state.iter().any(|&u| u > 1.05));
+    assert!(limited.conservation_drift().abs() < 1e-12);
+
+    let error = |dx: f64| {
+        let dt = 0.4 * dx;
+        let mut sim = Simluation::new(dx, dt, [-1., 1.], |x| (PI * x).sin());
+        let steps = (0.5 / dt).round() as usize;
+        for _ in 0..steps {
+            sim.set_state(muscl.run_with(&sim, &eq, &SspRk3).unwrap());
+        }
+        let t = steps as f64 * dt;
+        sim.error_norms(&|x| (PI * (x - t)).sin()).l1
+    };
+    let ratio = error(1. / 32.) / error(1. / 64.);
+    assert!(ratio > 3., "ratio = {}", ratio);
+}
+
+#[test]
+fn boxed_schemes_clone_into_independent_copies() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    let mut copy = sim.clone();
+
+    let upwind: Box<dyn Scheme<f64>> = Box::new(Upwind);
+    let cloned = upwind.clone();
+    for _ in 0..10 {
+        sim.step(&*upwind, &eq).unwrap();
+        copy.step(&*cloned, &eq).unwrap();
+    }
+    assert_eq!(sim.state, copy.state);
+
+    // the copy keeps its own weight
+    let base: BoxedScheme = Box::new(Blend::new(Box::new(Upwind), Box::new(LaxWendroff), 0.));
+    let mut blend = Blend::new(base.clone(), Box::new(LaxWendroff), 0.);
+    let copy = blend.clone();
+    blend.set_theta(1.);
+    assert_eq!(copy.theta(), 0.);
+    assert_eq!(
+        blend.describe().name,
+        "Blend(Blend(Upwind, LaxWendroff), LaxWendroff)"
+    );
+    assert_eq!(copy.run(&sim, &eq).unwrap(), Upwind.run(&sim, &eq).unwrap());
+}
+
+#[test]
+#[should_panic(expected = "theta must be within [0, 1]")]
+fn blend_weight_outside_the_unit_interval_is_rejected() {
+    Blend::<f64>::new(Box::new(Upwind), Box::new(LaxWendroff), 1.5);
+}
+
+// neither `Send` nor `'static`, counting its calls in a shared cell
+#[derive(Debug, Clone)]
+struct Counting<'a> {
+    scheme: &'a dyn Scheme<f64>,
+    calls: Rc<Cell<usize>>,
+}
+
+impl Scheme<f64> for Counting<'_> {
+    fn flux_with(&self, extended: &Extended<f64>) -> Result<[Array1<f64>; 2], SchemeError> {
+        self.calls.set(self.calls.get() + 1);
+        self.scheme.flux_with(extended)
+    }
+}
+
+#[test]
+fn boxed_schemes_clone_without_being_thread_safe() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+
+    let upwind = Upwind;
+    let calls = Rc::new(Cell::new(0));
+    let boxed: Box<dyn Scheme<f64> + '_> = Box::new(Counting {
+        scheme: &upwind,
+        calls: calls.clone(),
+    });
+    let cloned = boxed.clone();
+    assert_eq!(Rc::strong_count(&calls), 3);
+
+    let expected = Upwind.run(&sim, &eq).unwrap();
+    assert_eq!(boxed.run(&sim, &eq).unwrap(), expected);
+    let before = calls.get();
+    assert_eq!(cloned.run(&sim, &eq).unwrap(), expected);
+    assert!(calls.get() > before);
+}
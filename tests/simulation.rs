@@ -0,0 +1,926 @@
+use fdm::base::{Simluation, StepMode};
+use fdm::boundary::{Boundary, BoundaryKind, GhostFill};
+use fdm::equations::{Advection, InviscidBurger};
+use fdm::error::{FdError, SchemeError};
+use fdm::probes::{OutOfDomain, ProbeRecorder};
+use fdm::schemes::{
+    BeamWarming, LaxFriedrichs, LaxWendroff, Roe, Scheme, SingleStepLaxWendroff, Upwind,
+};
+use ndarray::s;
+use std::f64::consts::PI;
+
+#[test]
+fn with_cfl_derives_dt_from_max_speed() {
+    let dx = 1e-2;
+    let eq = Advection { a: 2.0 };
+    let sim = Simluation::with_cfl(dx, 0.6, [-1., 1.], |x: f64| x.sin(), &eq, 1e-3);
+    assert!((sim.dt_over_dx() * dx - 0.6 * dx / 2.).abs() < 1e-15);
+
+    // Burgers at rest has no speed to derive dt from
+    let rest = Simluation::with_cfl(dx, 0.6, [-1., 1.], |_: f64| 0., &InviscidBurger, 1e-3);
+    assert_eq!(rest.dt(), 1e-3);
+}
+
+#[test]
+fn stable_dt_tracks_the_current_maximum_speed() {
+    let dx = 1. / 32.;
+    let mut sim = Simluation::new(dx, dx, [-1., 1.], |x: f64| 2. * (PI * x).sin());
+    assert!((sim.stable_dt(&InviscidBurger, 0.8) - 0.4 * dx).abs() < 1e-12);
+
+    // faster waves shrink the step, a state at rest falls back to unit speed
+    sim.set_state_from_fn(|x| -4. * x);
+    assert!((sim.stable_dt(&InviscidBurger, 0.8) - 0.2 * dx).abs() < 1e-15);
+    sim.set_state_from_fn(|_| 0.);
+    assert_eq!(sim.stable_dt(&InviscidBurger, 0.8), 0.8 * dx);
+    sim.set_fallback_dt(0.1 * dx);
+    assert_eq!(sim.stable_dt(&InviscidBurger, 0.8), 0.1 * dx);
+}
+
+#[test]
+fn grid_index_of_maps_coordinates_to_nearest_point() {
+    let sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| x);
+    for k in 0..sim.len() {
+        assert_eq!(sim.grid_index_of(sim.grid[k]), Some(k));
+    }
+
+    // a point a third of the way between nodes 3 and 4 is nearer to 3
+    let x = sim.grid[3] + 0.1 / 3.;
+    assert_eq!(sim.grid_index_of(x), Some(3));
+    assert_eq!(sim.cell_containing(x), Some(3));
+    assert_eq!(sim.grid_index_of(x + 0.05), Some(4));
+
+    assert_eq!(sim.grid_index_of(-2.), None);
+    assert_eq!(sim.grid_index_of(2.), None);
+    assert_eq!(sim.cell_containing(-1.01), None);
+
+    let empty = Simluation::new(0.1, 0.05, [0., 0.], |x: f64| x);
+    assert!(empty.is_empty());
+    assert_eq!(empty.grid_index_of(0.), None);
+    assert_eq!(empty.cell_containing(0.), None);
+}
+
+#[test]
+fn tracked_shock_moves_at_rankine_hugoniot_speed() {
+    let dx = 1. / 64.;
+    let mut sim = Simluation::new(
+        dx,
+        0.5 * dx,
+        [-1., 1.],
+        |x: f64| if x < 0. { 1. } else { 0. },
+    );
+    sim.set_boundary(Boundary::Dirichlet([1., 0.]));
+
+    // s = (f(1) - f(0)) / (1 - 0) = 1/2
+    let steps = 128;
+    sim.enable_history();
+    let trajectory = sim
+        .track_shock(&Upwind, &InviscidBurger, 1., steps)
+        .unwrap();
+    assert_eq!(trajectory.len(), steps);
+    // tracking steps the simulation, so the clock and the history follow
+    assert_eq!(trajectory[steps - 1].0, sim.time());
+    assert_eq!(sim.history().len(), steps);
+    for (t, x) in trajectory.into_iter() {
+        assert!((x - 0.5 * t).abs() < 3. * dx, "x = {} at t = {}", x, t);
+    }
+}
+
+#[test]
+fn linear_ghost_fill_is_exact_for_linear_profile() {
+    let dx = 0.1;
+    let mut sim = Simluation::new(dx, 0.05, [0., 1.], |x: f64| 2. * x + 1.);
+    sim.set_ghost_fill(Some(GhostFill::Linear), Some(GhostFill::Linear));
+
+    let ext = 3;
+    let u = sim.get_u(ext);
+    let x0 = sim.grid[0] - ext as f64 * dx;
+    for (j, u) in u.iter().enumerate() {
+        let x = x0 + j as f64 * dx;
+        assert!((u - (2. * x + 1.)).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn pulse_does_not_pass_through_obstacle() {
+    let dx = 1. / 32.;
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| {
+        (-(x + 0.5).powi(2) * 400.).exp()
+    });
+    sim.set_boundary(Boundary::Dirichlet([0., 0.]));
+    let gate = sim.grid_index_of(0.).unwrap();
+    sim.set_obstacle(&[gate]);
+
+    let eq = Advection { a: 1.0 };
+    for _ in 0..64 {
+        sim.set_state(Upwind.run(&sim, &eq).unwrap());
+    }
+
+    assert!(sim.state[gate].abs() < 1e-12);
+    assert!(sim.state.iter().skip(gate).all(|u| u.abs() < 1e-12));
+}
+
+#[test]
+fn upwind_dissipates_energy_unlike_exact_shift() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+
+    let previous = sim.state.clone();
+    sim.set_state(Upwind.run(&sim, &eq).unwrap());
+    assert!(sim.energy_dissipation_rate(&previous) < -1e-3);
+
+    // shifting by exactly one cell is the non-dissipative reference
+    let previous = sim.state.clone();
+    let n = sim.len();
+    let shifted = (0..n).map(|j| previous[(j + n - 1) % n]).collect();
+    sim.set_state(shifted);
+    assert!(sim.energy_dissipation_rate(&previous).abs() < 1e-12);
+}
+
+#[test]
+fn probes_record_one_sample_per_step() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    let mut recorder = ProbeRecorder::new(vec![-0.3, 0.45]);
+
+    let steps = 10;
+    sim.probe_series(&Upwind, &eq, &mut recorder, steps)
+        .unwrap();
+    assert!((sim.time() - steps as f64 * sim.dt()).abs() < 1e-12);
+
+    for (&x, series) in recorder.probes.iter().zip(recorder.series()) {
+        assert_eq!(series.len(), steps);
+        assert_eq!(*series.last().unwrap(), sim.sample_at(x).unwrap());
+    }
+}
+
+#[test]
+fn probes_outside_domain_record_nan_or_nothing() {
+    let sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| x);
+    let mut recorder = ProbeRecorder::new(vec![0.25, 5.]);
+    recorder.record(&sim);
+    assert!((recorder.series()[0][0] - 0.25).abs() < 1e-12);
+    assert!(recorder.series()[1][0].is_nan());
+
+    recorder.out_of_domain = OutOfDomain::Skip;
+    recorder.record(&sim);
+    assert_eq!(recorder.series()[1].len(), 1);
+}
+
+#[test]
+fn forward_euler_from_rk_substep_matches_run() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    let expected = Upwind.run(&sim, &eq).unwrap();
+
+    // u^{n+1} = u^n + dt/dx (h_{j-} - h_{j+})
+    let [h_neg, h_pos] = Upwind.flux(&sim, &eq).unwrap();
+    let next = sim.rk_substep(&(h_neg - h_pos), sim.dt_over_dx());
+    assert_eq!(next, expected);
+}
+
+#[test]
+fn upwind_keeps_maximum_principle_unlike_lax_wendroff() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let square = |x: f64| if (-0.25..=0.25).contains(&x) { 1. } else { 0. };
+    let mut upwind = Simluation::new(dx, 0.5 * dx, [-1., 1.], square);
+    let mut lax_wendroff = Simluation::new(dx, 0.5 * dx, [-1., 1.], square);
+
+    let mut lw_violation: f64 = 0.;
+    for _ in 0..20 {
+        let previous = upwind.state.clone();
+        upwind.set_state(Upwind.run(&upwind, &eq).unwrap());
+        assert_eq!(upwind.max_principle_check(&previous), (true, 0.));
+
+        let previous = lax_wendroff.state.clone();
+        lax_wendroff.set_state(LaxWendroff.run(&lax_wendroff, &eq).unwrap());
+        let (_, violation) = lax_wendroff.max_principle_check(&previous);
+        lw_violation = lw_violation.max(violation);
+    }
+    assert!(lw_violation > 1e-2, "violation = {}", lw_violation);
+}
+
+#[test]
+fn coordinate_slice_matches_manual_indexing() {
+    let sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| x * x);
+    let range = sim.index_range([-0.25, 0.35]);
+
+    let expected: Vec<usize> = (0..sim.len())
+        .filter(|&j| (-0.25..=0.35).contains(&sim.grid[j]))
+        .collect();
+    assert_eq!(range, expected[0]..expected[expected.len() - 1] + 1);
+
+    let grid = sim.grid_slice(range.clone());
+    let state = sim.state_slice(range);
+    assert_eq!(grid.len(), expected.len());
+    for (k, &j) in expected.iter().enumerate() {
+        assert_eq!(grid[k], sim.grid[j]);
+        assert_eq!(state[k], sim.state[j]);
+    }
+
+    // out-of-range indices are clamped to the grid
+    assert_eq!(sim.state_slice(15..100).len(), sim.len() - 15);
+    assert!(sim.state_slice(100..200).is_empty());
+    assert!(sim.index_range([2., 3.]).is_empty());
+}
+
+#[test]
+fn set_state_from_fn_reinitializes_over_grid() {
+    let mut sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| (PI * x).sin());
+    sim.set_state(Upwind.run(&sim, &Advection { a: 1.0 }).unwrap());
+
+    sim.set_state_from_fn(|x| x);
+    assert_eq!(sim.state, sim.grid);
+}
+
+#[test]
+fn centered_pulse_exits_after_half_domain_over_speed() {
+    let dx = 1. / 64.;
+    let sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (-x.powi(2) * 4000.).exp());
+
+    let t = sim.time_to_exit(&Advection { a: -2.0 }).unwrap();
+    assert!((t - 1. / 2.).abs() < dx, "t = {}", t);
+
+    assert_eq!(sim.time_to_exit(&Advection { a: 0.0 }), None);
+}
+
+#[test]
+fn validate_reports_every_problem() {
+    let dx = 0.1;
+    let eq = Advection { a: 1.0 };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| x);
+    assert_eq!(sim.validate(&Upwind, &eq), Ok(()));
+
+    sim = Simluation::new(dx, 2. * dx, [-1., 1.], |x: f64| x);
+    sim.set_boundary(Boundary::Dirichlet([f64::NAN, 0.]));
+    let errors = sim.validate(&Upwind, &eq).unwrap_err();
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(errors.contains(&FdError::CflViolated(2.)));
+    assert!(matches!(errors[1], FdError::InvalidBoundary(_)));
+}
+
+#[test]
+fn validate_reports_non_positive_steps() {
+    let eq = Advection { a: 1.0 };
+    // no cell fits in between
+    let sim = Simluation::new(-0.1, 0., [-1., 1.], |x: f64| x);
+    assert_eq!(
+        sim.validate(&Upwind, &eq),
+        Err(vec![
+            FdError::NonPositiveDx(-0.1),
+            FdError::NonPositiveDt(0.)
+        ])
+    );
+}
+
+#[test]
+fn validate_reports_a_state_not_matching_the_grid() {
+    let mut sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| x);
+    sim.state = sim.state.slice(s![..5]).to_owned();
+    assert_eq!(
+        sim.validate(&Upwind, &Advection { a: 1.0 }),
+        Err(vec![FdError::LengthMismatch { grid: 20, state: 5 }])
+    );
+}
+
+#[test]
+fn validate_reports_nonlinear_flux_for_linear_only_schemes() {
+    let sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| 0.5 * x);
+    for scheme in [&BeamWarming as &dyn Scheme<f64>, &SingleStepLaxWendroff].iter() {
+        assert_eq!(sim.validate(*scheme, &Advection { a: 1.0 }), Ok(()));
+        assert_eq!(
+            sim.validate(*scheme, &InviscidBurger),
+            Err(vec![FdError::NonlinearFlux])
+        );
+    }
+    assert_eq!(sim.validate(&LaxWendroff, &InviscidBurger), Ok(()));
+}
+
+#[test]
+fn validate_reports_a_grid_too_short_for_the_ghost_fill() {
+    let mut sim = Simluation::new(0.5, 0.25, [0., 1.], |x: f64| x);
+    sim.set_ghost_fill(Some(GhostFill::Quadratic), None);
+    assert_eq!(
+        sim.validate(&Upwind, &Advection { a: 1.0 }),
+        Err(vec![FdError::GridTooShort { needed: 3, len: 2 }])
+    );
+}
+
+#[test]
+fn approx_eq_compares_within_tolerance() {
+    let tol = 1e-8;
+    let sim = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| (PI * x).sin());
+    let near = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| (PI * x).sin() + tol / 2.);
+    let far = Simluation::new(0.1, 0.05, [-1., 1.], |x: f64| (PI * x).sin() - 2. * tol);
+
+    assert!(sim.approx_eq(&near, tol));
+    assert!(!sim.approx_eq(&far, tol));
+
+    let shorter = Simluation::new(0.1, 0.05, [-1., 0.9], |x: f64| (PI * x).sin());
+    assert!(!sim.approx_eq(&shorter, tol));
+}
+
+#[test]
+fn truncation_error_peaks_at_discontinuity() {
+    let dx = 1. / 64.;
+    let sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| {
+        0.1 * (PI * x).sin() + if (-0.25..0.25).contains(&x) { 1. } else { 0. }
+    });
+    let lte = sim.local_truncation_error(&Upwind, &Advection { a: 1.0 });
+
+    let max_in = |[a, b]: [f64; 2]| {
+        sim.grid
+            .iter()
+            .zip(lte.iter())
+            .filter(|(&x, _)| a <= x && x <= b)
+            .fold(0., |m: f64, (_, &e)| m.max(e))
+    };
+    assert!(max_in([0.5, 0.9]) < 1e-4, "{}", max_in([0.5, 0.9]));
+    assert!(max_in([-0.3, -0.2]) > 1e-2, "{}", max_in([-0.3, -0.2]));
+}
+
+#[test]
+fn periodic_run_keeps_relative_mass_error_at_machine_precision() {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| 1. + 0.5 * (PI * x).sin());
+    for _ in 0..200 {
+        sim.set_state(LaxWendroff.run(&sim, &eq).unwrap());
+    }
+    assert!(sim.relative_mass_error().abs() < 1e-12);
+
+    // zero initial mass falls back to the absolute drift
+    sim.set_state_from_fn(|_| 0.);
+    sim.set_state(sim.state.mapv(|u| u + 1.));
+    assert!((sim.relative_mass_error() - 2.).abs() < 1e-12);
+}
+
+#[test]
+fn only_non_dissipative_scheme_retraces_backward() {
+    let dx = 1. / 64.;
+    let eq = Advection { a: 1.0 };
+    let init = |x: f64| (PI * x).sin();
+    let round_trip = |scheme: &dyn Scheme<f64>| {
+        let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], init);
+        for _ in 0..64 {
+            sim.set_state(scheme.run(&sim, &eq).unwrap());
+        }
+        for _ in 0..64 {
+            sim.step_backward(scheme, &eq).unwrap();
+        }
+        sim.error_norms(&init).linf
+    };
+
+    assert!(
+        round_trip(&LaxWendroff) < 1e-3,
+        "{}",
+        round_trip(&LaxWendroff)
+    );
+    assert!(round_trip(&Upwind) > 1e-2, "{}", round_trip(&Upwind));
+
+    // too fast backward as well as forward
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], init);
+    let before = sim.clone();
+    assert!(sim.step_backward(&Upwind, &Advection { a: 4.0 }).is_err());
+    assert_eq!(sim.state, before.state);
+    assert_eq!((sim.time(), sim.dt()), (0., before.dt()));
+    assert_eq!(sim.step_backward(&Upwind, &eq), Ok(-0.5 * dx));
+}
+
+#[test]
+fn bound_equation_step_matches_explicit_step() {
+    let dx = 1. / 32.;
+    let init = |x: f64| 0.5 + 0.25 * (PI * x).sin();
+    let mut explicit = Simluation::new(dx, 0.5 * dx, [-1., 1.], init);
+    let mut bound = Simluation::new(dx, 0.5 * dx, [-1., 1.], init);
+    bound.bind_equation(Box::new(InviscidBurger));
+
+    for _ in 0..10 {
+        explicit.set_state(LaxWendroff.run(&explicit, &InviscidBurger).unwrap());
+        bound.advance(&LaxWendroff).unwrap();
+    }
+    assert_eq!(bound.state, explicit.state);
+}
+
+#[test]
+fn conservative_schemes_share_interface_fluxes() {
+    let dx = 1. / 32.;
+    let sim = Simluation::new(dx, 0.4 * dx, [-1., 1.], |x: f64| 0.5 + (PI * x).sin());
+    let schemes: [&dyn Scheme<f64>; 5] = [
+        &Upwind,
+        &BeamWarming,
+        &LaxWendroff,
+        &LaxFriedrichs,
+        &Roe { entropy_fix: true },
+    ];
+    for scheme in schemes.iter() {
+        let (h_neg, h_pos) = sim.interface_flux(*scheme, &InviscidBurger).unwrap();
+        assert_eq!(h_neg.len(), sim.len());
+        for j in 0..sim.len() - 1 {
+            assert_eq!(h_pos[j], h_neg[j + 1], "{:?} at {}", scheme, j);
+        }
+    }
+}
+
+#[test]
+fn artificial_viscosity_only_acts_near_the_jump() {
+    let dx = 1. / 32.;
+    let init = |x: f64| {
+        if x < 0. {
+            1. + 0.1 * x
+        } else {
+            0.5 + 0.1 * (PI * x).sin()
+        }
+    };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], init);
+    for _ in 0..4 {
+        sim.set_state(LaxWendroff.run(&sim, &Advection { a: 1.0 }).unwrap());
+    }
+    let before = sim.clone();
+    let flagged = sim.shock_sensor(0.05);
+    let mass = sim.mass();
+
+    let count = sim.apply_artificial_viscosity(0.25, 0.05);
+    assert!(count > 0 && count < 16, "{} cells flagged", count);
+    assert!((sim.mass() - mass).abs() < 1e-14);
+
+    let n = sim.len();
+    for j in 0..n {
+        let near = [j + n - 1, j, j + 1].iter().any(|&i| flagged[i % n]);
+        if near {
+            continue;
+        }
+        assert_eq!(sim.state[j], before.state[j], "cell {} changed", j);
+    }
+    // the jumps sit around x = 0.0625 and the periodic wrap after four steps
+    let jump = sim.grid_index_of(0.0625).unwrap();
+    assert!(flagged[jump - 2..=jump + 2].iter().any(|&f| f));
+    assert!(sim.total_variation() < before.total_variation());
+}
+
+#[test]
+fn flux_limited_mass_fix_restores_mass_within_bounds() {
+    let dx = 1. / 32.;
+    let square = |x: f64| if (-0.25..=0.25).contains(&x) { 1. } else { 0. };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], square);
+    for _ in 0..20 {
+        sim.set_state(LaxWendroff.run(&sim, &Advection { a: 1.0 }).unwrap());
+    }
+    let reference = sim.state.clone();
+    let mass = sim.mass();
+
+    // clamping the over- and undershoots changes the mass
+    sim.set_state(reference.mapv(|u| u.clamp(0., 1.)));
+    assert!((sim.mass() - mass).abs() > 1e-5);
+
+    let unplaced = sim.flux_limited_mass_fix(&reference, [0., 1.]);
+    assert_eq!(unplaced, 0.);
+    assert!((sim.mass() - mass).abs() < 1e-14);
+    assert!(sim.state.iter().all(|&u| (0. ..=1.).contains(&u)));
+}
+
+#[test]
+fn mass_change_balances_the_boundary_fluxes() {
+    let dx = 1. / 32.;
+    let pulse = |x: f64| (-40. * (x - 0.7) * (x - 0.7)).exp();
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], pulse);
+    sim.set_boundary(Boundary::Dirichlet([0.2, 0.]));
+    sim.set_ghost_fill(None, Some(GhostFill::Constant));
+
+    let mut outflow = 0.;
+    for _ in 0..40 {
+        let mass = sim.mass();
+        let [left, right] = sim
+            .boundary_flux(&LaxWendroff, &Advection { a: 1.0 })
+            .unwrap();
+        sim.set_state(LaxWendroff.run(&sim, &Advection { a: 1.0 }).unwrap());
+        assert!((sim.mass() - mass - (left - right)).abs() < 1e-14);
+        outflow += right;
+    }
+    // the pulse leaves through the right boundary
+    assert!(outflow > 0.1);
+}
+
+#[test]
+fn advection_characteristics_are_parallel_lines() {
+    let sim = Simluation::new(1. / 32., 1. / 64., [-1., 1.], |x: f64| (PI * x).sin());
+    let starts = [-0.75, -0.25, 0.25, 0.75, 5.];
+    let curves = sim.export_characteristics(&Advection { a: 0.5 }, &starts, 1., 10);
+    assert_eq!(curves.len(), 4);
+    for (curve, x0) in curves.iter().zip(starts.iter()) {
+        assert_eq!(curve.len(), 11);
+        for &(t, x) in curve.iter() {
+            assert!((x - (x0 + 0.5 * t)).abs() < 1e-14);
+        }
+    }
+
+    // under Burgers those from the decreasing part of the sine cross
+    let curves = sim.export_characteristics(&InviscidBurger, &[0.5, 0.9], 1., 10);
+    let (_, left) = curves[0][10];
+    let (_, right) = curves[1][10];
+    assert!(left > right);
+}
+
+#[test]
+fn compare_to_profiles_the_difference_between_runs() {
+    let dx = 1. / 32.;
+    let sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| 2. * x + 1.);
+    assert!(sim.compare_to(&sim.clone()).iter().all(|&e| e == 0.));
+
+    let mut raised = sim.clone();
+    raised.set_state(&sim.state + 0.25);
+    assert!(sim.compare_to(&raised).iter().all(|&e| e == -0.25));
+
+    // on a grid shifted by dx / 2 the linear profile is resampled exactly
+    let shifted = Simluation::new(dx, 0.5 * dx, [-1. + dx / 2., 1.], |x: f64| 2. * x);
+    let profile = sim.compare_to(&shifted);
+    assert!(profile[0].is_nan());
+    assert!(profile.iter().skip(1).all(|&e| (e - 1.).abs() < 1e-14));
+}
+
+#[test]
+fn step_advances_in_place_and_counts_time() {
+    let dx = 1. / 32.;
+    let dt = 0.5 * dx;
+    let mut sim = Simluation::new(dx, dt, [-1., 1.], |x: f64| (PI * x).sin());
+    let mut explicit = sim.clone();
+    assert_eq!(sim.time(), 0.);
+    assert_eq!(Simluation::<f64>::default().time(), 0.);
+
+    for i in 1..=10 {
+        explicit.set_state(Upwind.run(&explicit, &Advection { a: 1.0 }).unwrap());
+        let t = sim.step(&Upwind, &Advection { a: 1.0 }).unwrap();
+        assert!((t - i as f64 * dt).abs() < 1e-15);
+        assert_eq!(sim.time(), t);
+    }
+    assert_eq!(sim.state, explicit.state);
+
+    // a failing step leaves the simulation untouched
+    let before = sim.clone();
+    assert!(sim.step(&Upwind, &Advection { a: 4.0 }).is_err());
+    assert_eq!(sim.state, before.state);
+    assert_eq!(sim.time(), before.time());
+
+    sim.set_state_from_fn(|x| x);
+    assert_eq!(sim.time(), 0.);
+}
+
+#[test]
+fn run_until_lands_exactly_on_the_target_time() {
+    let dx = 1. / 32.;
+    let dt = 0.5 * dx;
+    let advection = Advection { a: 1.0 };
+    let mut sim = Simluation::new(dx, dt, [-1., 1.], |x: f64| (PI * x).sin());
+    assert_eq!(
+        sim.run_until(10. * dt, &Upwind, &advection, StepMode::Fixed)
+            .unwrap()
+            .len(),
+        10
+    );
+    assert_eq!(sim.time(), 10. * dt);
+
+    // the last step is shortened and dt is restored afterwards
+    let dts = sim.run_until(12.5 * dt, &Upwind, &advection, StepMode::Fixed);
+    assert_eq!(dts.unwrap(), vec![dt, dt, 0.5 * dt]);
+    assert_eq!(sim.time(), 12.5 * dt);
+    assert_eq!(sim.dt_over_dx(), 0.5);
+    assert_eq!(
+        sim.run_until(12.5 * dt, &Upwind, &advection, StepMode::Fixed)
+            .unwrap()
+            .len(),
+        0
+    );
+
+    // too fast for a full step, but fine for the reduced one
+    let fast = Advection { a: 3.0 };
+    let mut sim = Simluation::new(dx, dt, [-1., 1.], |x: f64| (PI * x).sin());
+    assert!(sim.clone().step(&Upwind, &fast).is_err());
+    assert_eq!(
+        sim.run_until(0.5 * dt, &Upwind, &fast, StepMode::Fixed)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(sim.time(), 0.5 * dt);
+    assert!(sim
+        .run_until(2. * dt, &Upwind, &fast, StepMode::Fixed)
+        .is_err());
+    assert_eq!(sim.dt_over_dx(), 0.5);
+}
+
+#[test]
+fn history_records_every_step_once_enabled() {
+    let dx = 1. / 32.;
+    let advection = Advection { a: 1.0 };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| (PI * x).sin());
+    sim.step(&Upwind, &advection).unwrap();
+    assert!(sim.history().is_empty());
+
+    sim.enable_history();
+    let mut expected = vec![];
+    for _ in 0..3 {
+        sim.step(&Upwind, &advection).unwrap();
+        expected.push(sim.state.clone());
+    }
+    assert_eq!(sim.history(), &expected[..]);
+
+    sim.clear_history();
+    assert!(sim.history().is_empty());
+    sim.run_until(sim.time() + dx, &Upwind, &advection, StepMode::Fixed)
+        .unwrap();
+    assert_eq!(sim.history().len(), 2);
+    assert_eq!(sim.history()[1], sim.state);
+}
+
+#[test]
+fn lax_friedrichs_conserves_burgers_mass_unlike_the_advective_form() {
+    let dx = 1. / 32.;
+    let dt = 0.4 * dx;
+    let init = |x: f64| if x.abs() < 0.5 { 1.5 } else { 0.5 };
+    let mut conservative = Simluation::new(dx, dt, [-1., 1.], init);
+    let mut advective = conservative.clone();
+    for _ in 0..40 {
+        conservative.step(&LaxFriedrichs, &InviscidBurger).unwrap();
+
+        // upwinded u_t + u u_x = 0, which misses the Rankine-Hugoniot speed
+        let u = advective.state.clone();
+        let n = u.len();
+        advective.set_state(
+            (0..n)
+                .map(|j| u[j] - dt / dx * u[j] * (u[j] - u[(j + n - 1) % n]))
+                .collect(),
+        );
+    }
+    assert!(conservative.conservation_drift().abs() < 1e-12);
+    assert!(advective.conservation_drift().abs() > 1e-2);
+}
+
+#[test]
+fn neumann_boundary_lets_a_pulse_leave_the_domain() {
+    let dx = 1. / 32.;
+    let pulse = |x: f64| (-64. * x * x).exp();
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], pulse);
+    sim.set_boundary(Boundary::Neumann);
+    let u = sim.get_u(2);
+    let n = sim.len();
+    assert_eq!([u[0], u[1]], [sim.state[0]; 2]);
+    assert_eq!([u[n + 2], u[n + 3]], [sim.state[n - 1]; 2]);
+
+    let mass = sim.mass();
+    sim.run_until(2., &Upwind, &Advection { a: 1.0 }, StepMode::Fixed)
+        .unwrap();
+    assert!(sim.mass() < 1e-3 * mass, "{}", sim.mass());
+    assert!(!sim.is_periodic());
+}
+
+#[test]
+fn boundaries_are_set_independently_per_side() {
+    let dx = 1. / 32.;
+    let pulse = |x: f64| (-64. * x * x).exp();
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], pulse)
+        .with_boundaries(BoundaryKind::Dirichlet(0.25), BoundaryKind::Neumann);
+    assert_eq!(
+        sim.boundaries(),
+        [BoundaryKind::Dirichlet(0.25), BoundaryKind::Neumann]
+    );
+    let u = sim.get_u(2);
+    let n = sim.len();
+    assert_eq!([u[0], u[1]], [0.25; 2]);
+    assert_eq!([u[n + 2], u[n + 3]], [sim.state[n - 1]; 2]);
+
+    // the pulse leaves on the right while the inflow fills up the domain
+    sim.run_until(3., &Upwind, &Advection { a: 1.0 }, StepMode::Fixed)
+        .unwrap();
+    assert!(sim.state.iter().all(|&u| (u - 0.25).abs() < 1e-3));
+
+    sim.set_boundary(Boundary::Periodic);
+    assert_eq!(sim.boundaries(), [BoundaryKind::Periodic; 2]);
+    assert!(sim.is_periodic());
+}
+
+#[test]
+fn reflective_walls_mirror_the_interior_and_keep_symmetry() {
+    let dx = 1. / 32.;
+    // symmetric about the center x = -dx / 2 of the grid
+    let bump = |x: f64| (-16. * (x + dx / 2.).powi(2)).exp();
+    let mut right = Simluation::new(dx, 0.5 * dx, [-1., 1.], bump);
+    right.set_boundary(Boundary::Reflective);
+    let u = right.get_u(3);
+    let n = right.len();
+    for i in 0..3 {
+        assert_eq!(u[2 - i], right.state[i]);
+        assert_eq!(u[n + 3 + i], right.state[n - 1 - i]);
+    }
+
+    // flipping the direction of transport mirrors the whole run, walls included
+    let mut left = right.clone();
+    for _ in 0..200 {
+        right.step(&LaxFriedrichs, &Advection { a: 1.0 }).unwrap();
+        left.step(&LaxFriedrichs, &Advection { a: -1.0 }).unwrap();
+    }
+    for j in 0..n {
+        assert!((right.state[j] - left.state[n - 1 - j]).abs() < 1e-12);
+    }
+
+    // without transport the centered bump spreads onto both walls alike
+    let mut still = Simluation::new(dx, 0.5 * dx, [-1., 1.], bump);
+    still.set_boundary(Boundary::Reflective);
+    let mass = still.mass();
+    for _ in 0..2000 {
+        still.step(&LaxFriedrichs, &Advection { a: 0.0 }).unwrap();
+    }
+    assert!(still.state[0] > 0.1);
+    for j in 0..n {
+        assert!((still.state[j] - still.state[n - 1 - j]).abs() < 1e-12);
+    }
+    assert!((still.mass() - mass).abs() < 1e-12);
+}
+
+#[test]
+fn adaptive_run_until_follows_the_decaying_burgers_speed() {
+    let dx = 1. / 32.;
+    let init = |x: f64| 2. * (PI * x).sin();
+    let mut fixed = Simluation::new(dx, dx, [-1., 1.], init);
+    assert!(fixed
+        .run_until(1., &Upwind, &InviscidBurger, StepMode::Fixed)
+        .is_err());
+
+    let mut sim = Simluation::new(dx, dx, [-1., 1.], init);
+    let mode = StepMode::Adaptive { cfl: 0.8 };
+    let dts = sim.run_until(1., &Upwind, &InviscidBurger, mode).unwrap();
+    assert_eq!(sim.time(), 1.);
+    assert!((dts.iter().sum::<f64>() - 1.).abs() < 1e-12);
+    assert_eq!(sim.dt_over_dx(), 1.);
+
+    // the shock dissipates the amplitude, so the steps grow
+    assert!((dts[0] - 0.4 * dx).abs() < 1e-12);
+    assert!(dts[dts.len() - 2] > 1.5 * dts[0], "{:?}", dts);
+}
+
+#[test]
+fn run_until_rejects_time_steps_that_make_no_progress() {
+    let dx = 1. / 32.;
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |_| 0.);
+    for &cfl in [0., -0.5, f64::NAN].iter() {
+        let mode = StepMode::Adaptive { cfl };
+        match sim.run_until(1., &Upwind, &InviscidBurger, mode) {
+            Err(SchemeError::NonPositiveCfl(c)) => assert!(c == cfl || c.is_nan()),
+            other => panic!("cfl = {}: {:?}", cfl, other),
+        }
+    }
+
+    // at rest Burgers has no wave speed, so the fallback is all there is
+    sim.set_fallback_dt(0.);
+    let mode = StepMode::Adaptive { cfl: 0.8 };
+    assert_eq!(
+        sim.run_until(1., &Upwind, &InviscidBurger, mode),
+        Err(SchemeError::StalledTime { t: 0., dt: 0. })
+    );
+
+    let mut backward = Simluation::new(dx, -0.5 * dx, [-1., 1.], |_| 0.);
+    assert_eq!(
+        backward.run_until(1., &Upwind, &Advection { a: 1.0 }, StepMode::Fixed),
+        Err(SchemeError::StalledTime {
+            t: 0.,
+            dt: -0.5 * dx
+        })
+    );
+    assert_eq!(backward.time(), 0.);
+}
+
+#[test]
+fn dx_and_dt_read_the_configured_steps() {
+    let sim = Simluation::with_cells([-5., 5.], 1000, 1e-3, |x: f64| x);
+    assert!((sim.dx() - 0.01).abs() < 1e-15);
+    assert_eq!(sim.dt(), 1e-3);
+
+    // `run_until` restores the configured step after shortening the last one
+    let dt = 0.05;
+    let mut sim = Simluation::new(0.1, dt, [-1., 1.], |x: f64| x);
+    sim.run_until(2.5 * dt, &Upwind, &Advection { a: 1.0 }, StepMode::Fixed)
+        .unwrap();
+    assert_eq!((sim.dx(), sim.dt()), (0.1, dt));
+}
+
+#[test]
+fn with_cells_places_exactly_the_requested_cell_centers() {
+    let sim = Simluation::with_cells([-5., 5.], 1000, 1e-3, |x: f64| x);
+    assert_eq!(sim.len(), 1000);
+    assert!((sim.grid[0] + 4.995).abs() < 1e-12);
+    assert!((sim.grid[999] - 4.995).abs() < 1e-12);
+    assert!((sim.mass()).abs() < 1e-9);
+    assert!((sim.dt_over_dx() - 0.1).abs() < 1e-12);
+
+    // the half-open grid of `new` starts at the left end and misses the right one
+    let sim = Simluation::new(0.25, 0.1, [-1., 1.], |x: f64| x);
+    assert_eq!(
+        sim.grid.to_vec(),
+        vec![-1., -0.75, -0.5, -0.25, 0., 0.25, 0.5, 0.75]
+    );
+}
+
+#[test]
+fn switching_from_dirichlet_to_periodic_mid_run_conserves_mass() {
+    let dx = 1. / 32.;
+    let advection = Advection { a: 1.0 };
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |_: f64| 0.);
+    sim.set_boundary(Boundary::Dirichlet([1., 0.]));
+    for _ in 0..20 {
+        sim.step(&Upwind, &advection).unwrap();
+    }
+    // the inflow has filled part of the domain
+    let inflow = sim.mass();
+    assert!(inflow > 0.1, "{}", inflow);
+
+    sim.set_boundary(Boundary::Periodic);
+    let n = sim.len();
+    let u = sim.get_u(1);
+    assert_eq!([u[0], u[n + 1]], [sim.state[n - 1], sim.state[0]]);
+    for _ in 0..200 {
+        sim.step(&Upwind, &advection).unwrap();
+    }
+    assert!(
+        (sim.mass() - inflow).abs() < 1e-12,
+        "{}",
+        sim.mass() - inflow
+    );
+}
+
+#[test]
+fn simulations_can_be_shared_between_threads() {
+    fn shareable<S: Send + Sync>() {}
+    shareable::<Simluation<f64>>();
+}
+
+// the extended state built by prepending and appending one layer at a time,
+// as `get_u` did before filling a preallocated buffer
+fn get_u_by_insertion(
+    u: &[f64],
+    sides: [BoundaryKind<f64>; 2],
+    fills: [Option<GhostFill>; 2],
+    ext: usize,
+) -> Vec<f64> {
+    let n = u.len();
+    let left_inner: Vec<f64> = u.iter().take(3).cloned().collect();
+    let right_inner: Vec<f64> = u.iter().rev().take(3).cloned().collect();
+    let mut v = u.to_vec();
+    for i in 0..ext {
+        v.insert(
+            0,
+            match (fills[0], sides[0]) {
+                (Some(fill), _) => fill.extrapolate(&left_inner, i + 1),
+                (None, BoundaryKind::Dirichlet(b)) => b,
+                (None, BoundaryKind::Neumann) => u[0],
+                (None, BoundaryKind::Reflective) => u[i],
+                (None, BoundaryKind::Periodic) => u[n - 1 - i],
+            },
+        );
+        v.push(match (fills[1], sides[1]) {
+            (Some(fill), _) => fill.extrapolate(&right_inner, i + 1),
+            (None, BoundaryKind::Dirichlet(b)) => b,
+            (None, BoundaryKind::Neumann) => u[n - 1],
+            (None, BoundaryKind::Reflective) => u[n - 1 - i],
+            (None, BoundaryKind::Periodic) => u[i],
+        });
+    }
+    v
+}
+
+#[test]
+fn get_u_matches_the_insertion_reference() {
+    let kinds = [
+        BoundaryKind::Periodic,
+        BoundaryKind::Dirichlet(-0.5),
+        BoundaryKind::Neumann,
+        BoundaryKind::Reflective,
+    ];
+    let fills = [
+        [None, None],
+        [Some(GhostFill::Linear), Some(GhostFill::Quadratic)],
+        [Some(GhostFill::Constant), None],
+    ];
+    let mut sim = Simluation::new(0.25, 0.125, [-1., 1.], |x: f64| x * x + x);
+    let mut scratch = vec![];
+    for &left in kinds.iter() {
+        for &right in kinds.iter() {
+            for &[fill_left, fill_right] in fills.iter() {
+                sim.set_boundaries(left, right);
+                sim.set_ghost_fill(fill_left, fill_right);
+                for ext in 0..=3 {
+                    let expected = get_u_by_insertion(
+                        sim.state.as_slice().unwrap(),
+                        [left, right],
+                        [fill_left, fill_right],
+                        ext,
+                    );
+                    assert_eq!(sim.get_u(ext).to_vec(), expected);
+                    sim.get_u_into(ext, &mut scratch);
+                    assert_eq!(scratch, expected);
+                }
+            }
+        }
+    }
+}
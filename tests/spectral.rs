@@ -0,0 +1,166 @@
+use fdm::base::Simluation;
+use fdm::boundary::BoundaryKind;
+use fdm::equations::Advection;
+use fdm::schemes::{BeamWarming, LaxFriedrichs, LaxWendroff, Scheme, Upwind};
+use fdm::spectral::{FourierMode, Tone, WaveTrain};
+use std::f64::consts::PI;
+
+// |DFT|^2 of every wavenumber bin
+fn power_spectrum(u: &[f64]) -> Vec<f64> {
+    let n = u.len();
+    (0..n)
+        .map(|k| {
+            let (re, im) = u.iter().enumerate().fold((0., 0.), |(re, im), (j, u)| {
+                let theta = -2. * PI * (k * j) as f64 / n as f64;
+                (re + u * theta.cos(), im + u * theta.sin())
+            });
+            re * re + im * im
+        })
+        .collect()
+}
+
+#[test]
+fn single_mode_has_energy_only_in_its_bin() {
+    let mode = FourierMode {
+        amplitude: 0.5,
+        wavenumber: 4,
+        phase: 0.3,
+    };
+    let sim = Simluation::from_fourier_modes(1. / 32., 1. / 64., [-1., 1.], &[mode]);
+    let n = sim.len();
+    assert_eq!(n, 64);
+
+    let power = power_spectrum(sim.state.as_slice().unwrap());
+    let total: f64 = power.iter().sum();
+    for (k, p) in power.iter().enumerate() {
+        if k == 4 || k == n - 4 {
+            assert!((p / total - 0.5).abs() < 1e-12);
+        } else {
+            assert!(p / total < 1e-20, "bin {} has {}", k, p / total);
+        }
+    }
+}
+
+#[test]
+fn wave_packet_is_centered_with_peak_at_its_wavenumber() {
+    let dx = 1. / 64.;
+    let (x0, sigma) = (0.25, 0.125);
+    let mut sim = Simluation::new(dx, 0.5 * dx, [-1., 1.], |_| 0.);
+    sim.inject_wave_packet(x0, sigma, 16. * PI);
+
+    let center = sim.grid_index_of(x0).unwrap();
+    assert!((sim.state[center] - 1.).abs() < 1e-12);
+    for (&x, &u) in sim.grid.iter().zip(sim.state.iter()) {
+        let envelope = (-(x - x0).powi(2) / (2. * sigma * sigma)).exp();
+        assert!(u.abs() <= envelope + 1e-12);
+    }
+
+    // 16 pi is 16 periods over the domain of length 2
+    let n = sim.len();
+    let power = power_spectrum(sim.state.as_slice().unwrap());
+    let peak = (0..=n / 2)
+        .max_by(|&a, &b| power[a].partial_cmp(&power[b]).unwrap())
+        .unwrap();
+    assert_eq!(peak, 16);
+}
+
+#[test]
+fn lax_friedrichs_symbol_matches_analytic_form() {
+    let dx = 1. / 32.;
+    let nu = 0.6;
+    for &k_dx in [0.1f64, 0.5, 1., 2., 3.].iter() {
+        let k = k_dx / dx;
+        let g = Simluation::scheme_symbol(&LaxFriedrichs, &Advection { a: 1.0 }, dx, nu * dx, k);
+        assert!((g.re - k_dx.cos()).abs() < 1e-12, "k dx = {}", k_dx);
+        assert!((g.im + nu * k_dx.sin()).abs() < 1e-12, "k dx = {}", k_dx);
+        assert!(g.norm() <= 1. + 1e-12);
+    }
+}
+
+#[test]
+fn inflow_wave_train_injects_its_frequencies() {
+    let tone = |amplitude, frequency| Tone {
+        amplitude,
+        frequency,
+        phase: 0.2,
+    };
+    let train = WaveTrain {
+        mean: 1.,
+        tones: vec![tone(0.3, 3.), tone(0.1, 7.)],
+    };
+
+    // 64 steps over one unit of time, so frequency w lands in bin w
+    let dt = 1. / 64.;
+    let eq = Advection { a: 1.0 };
+    let mut sim = Simluation::new(2. * dt, dt, [-1., 1.], |_| 1.);
+    let injected: Vec<f64> = (0..64)
+        .map(|step| {
+            sim.set_inflow(&train, step as f64 * dt);
+            let ghost = sim.get_u(1)[0];
+            sim.set_state(Upwind.run(&sim, &eq).unwrap());
+            ghost - train.mean
+        })
+        .collect();
+
+    let power = power_spectrum(&injected);
+    let total: f64 = power.iter().sum();
+    for (k, p) in power.iter().enumerate().take(33) {
+        let expected = match k {
+            3 => 0.09 / 0.1,
+            7 => 0.01 / 0.1,
+            _ => 0.,
+        };
+        assert!(
+            (2. * p / total - expected).abs() < 1e-10,
+            "bin {} has {}",
+            k,
+            p / total
+        );
+    }
+    // the waves are carried into the domain
+    assert!(sim.state.iter().any(|&u| (u - 1.).abs() > 0.1));
+}
+
+#[test]
+fn inflow_replaces_only_a_periodic_right_boundary() {
+    let train = WaveTrain {
+        mean: 0.5,
+        tones: vec![],
+    };
+    let mut sim = Simluation::new(0.1, 0.05, [0., 1.], |_| 0.);
+    assert_eq!(sim.boundaries()[1], BoundaryKind::Periodic);
+    sim.set_inflow(&train, 0.);
+    assert_eq!(
+        sim.boundaries(),
+        [BoundaryKind::Dirichlet(0.5), BoundaryKind::Dirichlet(0.5)]
+    );
+
+    sim.set_boundaries(BoundaryKind::Periodic, BoundaryKind::Neumann);
+    sim.set_inflow(&train, 0.);
+    assert_eq!(
+        sim.boundaries(),
+        [BoundaryKind::Dirichlet(0.5), BoundaryKind::Neumann]
+    );
+}
+
+#[test]
+fn modified_wavenumbers_match_their_analytic_curves() {
+    for &theta in [0.1, 0.5, 1., 2., 3.].iter() {
+        let (s, c) = (f64::sin(theta), f64::cos(theta));
+        let (s2, c2) = ((2. * theta).sin(), (2. * theta).cos());
+        let expected = [
+            (Upwind.modified_wavenumber(theta), s, c - 1.),
+            // central in the semi-discrete limit
+            (LaxWendroff.modified_wavenumber(theta), s, 0.),
+            (
+                BeamWarming.modified_wavenumber(theta),
+                (4. * s - s2) / 2.,
+                -(3. - 4. * c + c2) / 2.,
+            ),
+        ];
+        for (k, re, im) in expected.iter() {
+            assert!((k.re - re).abs() < 1e-6, "{} vs {} at {}", k, re, theta);
+            assert!((k.im - im).abs() < 1e-6, "{} vs {} at {}", k, im, theta);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use fdm::base::Simluation;
+use fdm::equations::{Advection, RelaxedTraffic, Traffic};
+use fdm::schemes::{Scheme, Upwind};
+use ndarray::Array1;
+use std::f64::consts::PI;
+
+// weak decay at a rate varying in space, so that it does not commute with
+// the advection
+fn rate(x: f64) -> f64 {
+    0.25 * (1. + (PI * x).sin())
+}
+
+fn split_minus_unsplit(dt: f64) -> f64 {
+    let dx = 1. / 32.;
+    let eq = Advection { a: 1.0 };
+    let init = |x: f64| (PI * x).cos();
+    let mut split = Simluation::new(dx, dt, [-1., 1.], init);
+    let mut unsplit = Simluation::new(dx, dt, [-1., 1.], init);
+    let decay = |u: f64, x: f64, dt: f64| u * (-rate(x) * dt).exp();
+
+    for _ in 0..(0.5 / dt).round() as usize {
+        split.coupled_step(&Upwind, &eq, &decay).unwrap();
+
+        let source = unsplit.grid.mapv(rate) * &unsplit.state * dt;
+        unsplit.set_state(Upwind.run(&unsplit, &eq).unwrap() - source);
+    }
+
+    split
+        .state
+        .iter()
+        .zip(unsplit.state.iter())
+        .map(|(a, b)| (a - b).abs())
+        .fold(0., f64::max)
+}
+
+#[test]
+fn godunov_splitting_is_first_order_accurate() {
+    let dx = 1. / 32.;
+    let coarse = split_minus_unsplit(0.5 * dx);
+    let fine = split_minus_unsplit(0.25 * dx);
+
+    assert!(coarse < 1e-2, "difference = {}", coarse);
+    let order = (coarse / fine).log2();
+    assert!((order - 1.).abs() < 0.2, "order = {}", order);
+}
+
+fn traffic_run(tau: f64) -> (Simluation<f64>, Simluation<f64>) {
+    let dx = 1. / 64.;
+    let init = |x: f64| 0.5 + 0.3 * (2. * PI * x).sin();
+    let traffic = Traffic {
+        v_max: 1.,
+        rho_max: 1.,
+    };
+    let relaxed = RelaxedTraffic {
+        traffic,
+        tau,
+        equilibrium: |x: f64| 0.4 + 0.1 * (2. * PI * x).cos(),
+    };
+
+    let mut lwr = Simluation::new(dx, 0.5 * dx, [0., 1.], init);
+    let mut sim = Simluation::new(dx, 0.5 * dx, [0., 1.], init);
+    sim.enable_history();
+    for _ in 0..32 {
+        lwr.set_state(Upwind.run(&lwr, &traffic).unwrap());
+        let t = sim.coupled_step(&Upwind, &relaxed, &relaxed).unwrap();
+        assert_eq!(t, sim.time());
+        // the history holds the state after the source
+        assert_eq!(sim.history().last(), Some(&sim.state));
+    }
+    (lwr, sim)
+}
+
+#[test]
+fn slow_relaxation_matches_pure_lwr() {
+    let (lwr, relaxed) = traffic_run(1e6);
+    assert!(relaxed.approx_eq(&lwr, 1e-6));
+}
+
+#[test]
+fn fast_relaxation_tracks_equilibrium() {
+    let (_, relaxed) = traffic_run(1e-6);
+    for (&x, &rho) in relaxed.grid.iter().zip(relaxed.state.iter()) {
+        assert!((rho - (0.4 + 0.1 * (2. * PI * x).cos())).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn relaxation_is_a_forward_euler_source_when_run_directly() {
+    let dx = 1. / 64.;
+    let dt = 0.5 * dx;
+    let traffic = Traffic {
+        v_max: 1.,
+        rho_max: 1.,
+    };
+    let equilibrium = |x: f64| 0.4 + 0.1 * (2. * PI * x).cos();
+    let relaxed = RelaxedTraffic {
+        traffic,
+        tau: 0.5,
+        equilibrium,
+    };
+    let sim = Simluation::new(dx, dt, [0., 1.], |x: f64| 0.5 + 0.3 * (2. * PI * x).sin());
+
+    let lwr = Upwind.run(&sim, &traffic).unwrap();
+    let expected: Array1<f64> = lwr
+        .iter()
+        .zip(sim.grid.iter())
+        .map(|(&u, &x)| u + dt * (equilibrium(x) - u) / relaxed.tau)
+        .collect();
+    let run = Upwind.run(&sim, &relaxed).unwrap();
+    assert!((run - expected).iter().all(|e| e.abs() < 1e-14));
+}
@@ -0,0 +1,81 @@
+use fdm::equations::{Advection, InviscidBurger};
+use fdm::error::SchemeError;
+use fdm::schemes::{LaxFriedrichs, Upwind};
+use fdm::sweep::{run_sweep, Domain};
+use fdm::{BoxedEquation, BoxedFunction, BoxedScheme};
+use std::f64::consts::PI;
+
+#[test]
+fn sweep_populates_every_combination() {
+    let equations: Vec<(&str, BoxedEquation)> = vec![
+        ("Advection", Box::new(Advection { a: 1.0 })),
+        ("InviscidBurger", Box::new(InviscidBurger)),
+    ];
+    let inits: Vec<(&str, BoxedFunction)> = vec![
+        ("Sine", Box::new(|x: f64| (PI * x).sin())),
+        (
+            "Square",
+            Box::new(|x: f64| if x.abs() < 0.5 { 1. } else { 0. }),
+        ),
+    ];
+    let schemes: Vec<(&str, BoxedScheme)> = vec![
+        ("Upwind", Box::new(Upwind)),
+        ("LaxFriedrichs", Box::new(LaxFriedrichs)),
+    ];
+    let dx = 1. / 32.;
+    let domain = Domain {
+        dx,
+        dt: 0.5 * dx,
+        time: 0.25,
+        space: [-1., 1.],
+        keep_history: true,
+    };
+
+    let results = run_sweep(&equations, &inits, &schemes, &domain);
+    assert_eq!(results.len(), 8);
+    for (k, result) in results.iter().enumerate() {
+        assert_eq!(result.equation, equations[k / 4].0);
+        assert_eq!(result.init, inits[k / 2 % 2].0);
+        assert_eq!(result.scheme, schemes[k % 2].0);
+
+        assert_eq!(result.sim.len(), 64);
+        assert!(result.relative_mass_error.is_finite());
+        if result.init == "Square" {
+            assert!(result.relative_mass_error.abs() < 1e-12);
+        }
+        assert!(result.total_variation > 0.);
+        assert_eq!(result.error, None);
+        let history = result.history.as_ref().unwrap();
+        assert_eq!(history.len(), domain.steps());
+        assert_eq!(history.last().unwrap(), &result.sim.state);
+        assert!((result.sim.time() - domain.time).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn failing_runs_stop_without_affecting_the_others() {
+    let equations: Vec<(&str, BoxedEquation)> = vec![
+        ("Slow", Box::new(Advection { a: 0.5 })),
+        ("Fast", Box::new(Advection { a: 4.0 })),
+    ];
+    let inits: Vec<(&str, BoxedFunction)> = vec![("Sine", Box::new(|x: f64| (PI * x).sin()))];
+    let schemes: Vec<(&str, BoxedScheme)> = vec![("Upwind", Box::new(Upwind))];
+    let dx = 1. / 32.;
+    let domain = Domain {
+        dx,
+        dt: 0.5 * dx,
+        time: 0.25,
+        space: [-1., 1.],
+        keep_history: true,
+    };
+
+    let results = run_sweep(&equations, &inits, &schemes, &domain);
+    assert_eq!(results[0].error, None);
+    assert_eq!(results[0].history.as_ref().unwrap().len(), domain.steps());
+    match results[1].error {
+        Some(SchemeError::CflViolation { v, .. }) => assert_eq!(v, 2.),
+        ref other => panic!("expected a CFL violation, got {:?}", other),
+    }
+    assert!(results[1].history.as_ref().unwrap().is_empty());
+    assert_eq!(results[1].sim.time(), 0.);
+}
@@ -0,0 +1,154 @@
+use fdm::boundary::Boundary;
+use fdm::error::{BuildError, DryCellError, SchemeError};
+use fdm::systems::{ShallowWater, SystemEquation, SystemSimulation};
+use std::f64::consts::PI;
+
+/// Linear acoustics $p_t + K v_x = 0$, $v_t + p_x / \rho = 0$ with unit
+/// impedance, so the sound speed is $c = K$
+#[derive(Debug)]
+struct Acoustics {
+    c: f64,
+}
+
+impl SystemEquation<f64, 2> for Acoustics {
+    fn flux(&self, u: [f64; 2]) -> [f64; 2] {
+        [self.c * u[1], self.c * u[0]]
+    }
+
+    fn max_speed(&self, _: [f64; 2]) -> f64 {
+        self.c
+    }
+}
+
+#[test]
+fn rusanov_conserves_every_component_on_a_periodic_domain() {
+    let dx = 1. / 64.;
+    let mut sim = SystemSimulation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| {
+        [1. + (PI * x).sin(), (PI * x).cos()]
+    })
+    .unwrap();
+    assert_eq!(sim.len(), 128);
+    let before = sim.mass();
+    for _ in 0..100 {
+        sim.step(&Acoustics { c: 1. }).unwrap();
+    }
+    let after = sim.mass();
+    for k in 0..2 {
+        assert!(
+            (after[k] - before[k]).abs() < 1e-12,
+            "{:?} vs {:?}",
+            after,
+            before
+        );
+    }
+    assert!((sim.time() - 100. * sim.dt()).abs() < 1e-12);
+}
+
+#[test]
+fn pressure_pulse_splits_into_two_opposite_waves() {
+    let dx = 1. / 128.;
+    let pulse = |x: f64| (-(x / 0.1).powi(2)).exp();
+    let mut sim = SystemSimulation::new(dx, 0.5 * dx, [-1., 1.], |x: f64| [pulse(x), 0.]).unwrap();
+    sim.set_boundary(Boundary::Neumann);
+    let eq = Acoustics { c: 1. };
+    for _ in 0..(0.5 / sim.dt()).round() as usize {
+        sim.step(&eq).unwrap();
+    }
+
+    // half of the pulse travels each way, the velocity signing the direction
+    let peak = |from: usize, to: usize| {
+        (from..to)
+            .max_by(|&i, &j| sim.cell(i)[0].partial_cmp(&sim.cell(j)[0]).unwrap())
+            .unwrap()
+    };
+    let n = sim.len();
+    let (left, right) = (peak(0, n / 2), peak(n / 2, n));
+    assert!((sim.grid[left] + 0.5).abs() < 2. * dx, "{}", sim.grid[left]);
+    assert!(
+        (sim.grid[right] - 0.5).abs() < 2. * dx,
+        "{}",
+        sim.grid[right]
+    );
+    assert!(sim.cell(left)[1] < 0. && sim.cell(right)[1] > 0.);
+    assert!(sim.cell(left)[0] < 0.5 && sim.cell(left)[0] > 0.3);
+}
+
+#[test]
+fn step_beyond_the_cfl_limit_is_rejected() {
+    let dx = 0.1;
+    let mut sim = SystemSimulation::new(dx, dx, [0., 1.], |_: f64| [1., 0.]).unwrap();
+    let before = sim.state.clone();
+    match sim.step(&Acoustics { c: 2. }) {
+        Err(SchemeError::CflViolation { v, .. }) => assert_eq!(v, 2.),
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(sim.state, before);
+    assert_eq!(sim.time(), 0.);
+}
+
+#[test]
+fn dam_break_opens_a_rarefaction_and_a_shock() {
+    let dx = 1. / 128.;
+    let (g, h_left, h_right) = (1., 2., 1.);
+    let eq = ShallowWater { g };
+    let mut sim = SystemSimulation::new(dx, 0.25 * dx, [-1., 1.], |x: f64| {
+        [if x < 0. { h_left } else { h_right }, 0.]
+    })
+    .unwrap();
+    sim.set_boundary(Boundary::Neumann);
+    let before = sim.mass();
+    let t = 0.4;
+    for _ in 0..(t / sim.dt()).round() as usize {
+        sim.step(&eq).unwrap();
+    }
+    assert!((sim.mass()[0] - before[0]).abs() < 1e-12);
+
+    let h: Vec<f64> = (0..sim.len()).map(|j| sim.cell(j)[0]).collect();
+    let hu: Vec<f64> = (0..sim.len()).map(|j| sim.cell(j)[1]).collect();
+    // the water only flows downstream and its height does not increase
+    // along it, up to a slight dip of the middle state behind the shock
+    assert!(h.windows(2).all(|w| w[1] <= w[0] + 1e-4));
+    assert!(hu.iter().all(|&q| q >= -1e-12));
+
+    // undisturbed beyond the head of the rarefaction and ahead of the shock
+    let at = |x: f64| ((x + 1.) / dx).round() as usize;
+    let head = -(g * h_left).sqrt() * t;
+    assert!((h[at(head - 0.2)] - h_left).abs() < 1e-4);
+    assert!((h[at(0.9)] - h_right).abs() < 1e-4);
+
+    // the shock is steeper than the rarefaction
+    let steepest =
+        |range: std::ops::Range<usize>| range.map(|j| h[j] - h[j + 1]).fold(0., f64::max);
+    let n = sim.len();
+    assert!(steepest(n / 2..n - 1) > 3. * steepest(0..n / 2));
+}
+
+#[test]
+fn dry_cell_is_reported_before_stepping() {
+    let mut sim = SystemSimulation::new(0.1, 0.01, [0., 1.], |x: f64| {
+        [if x < 0.45 { 1. } else { 0. }, 0.]
+    })
+    .unwrap();
+    sim.set_boundary(Boundary::Neumann);
+    let before = sim.state.clone();
+    match sim.step(&ShallowWater { g: 9.81 }) {
+        Err(SchemeError::DryCell(DryCellError { h, location })) => {
+            assert_eq!((h, location), (0., 5))
+        }
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(sim.state, before);
+}
+
+#[test]
+fn a_grid_without_cells_is_rejected() {
+    let init = |_: f64| [1., 0.];
+    match SystemSimulation::<f64, 2>::new(0.1, 0.01, [1., 1.], init) {
+        Err(BuildError::EmptyRange(range)) => assert_eq!(range, [1., 1.]),
+        other => panic!("{:?}", other),
+    }
+    match SystemSimulation::<f64, 2>::new(-0.1, 0.01, [0., 1.], init) {
+        Err(BuildError::NonPositiveDx(dx)) => assert_eq!(dx, -0.1),
+        other => panic!("{:?}", other),
+    }
+}